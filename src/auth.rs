@@ -1,22 +1,50 @@
 //! Authentication strategies for the OpenRouter API.
 
-use crate::error::Result;
+use crate::error::{OpenRouterError, Result};
 use async_trait::async_trait;
-use reqwest::header::HeaderMap;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, Url};
 
 /// Authentication strategy trait.
 #[async_trait]
 pub trait AuthStrategy: Send + Sync {
     /// Apply authentication to the request headers.
     async fn apply(&self, headers: &mut HeaderMap) -> Result<()>;
+
+    /// Apply authentication with access to the request method and URL.
+    ///
+    /// Override this for signing schemes that cover request details (e.g., HMAC
+    /// over the path). Defaults to [`AuthStrategy::apply`].
+    async fn apply_signed(
+        &self,
+        method: &Method,
+        url: &Url,
+        headers: &mut HeaderMap,
+    ) -> Result<()> {
+        let _ = (method, url);
+        self.apply(headers).await
+    }
+
+    /// Validate the strategy's configuration.
+    ///
+    /// Called by [`ClientBuilder::try_build`](crate::ClientBuilder::try_build).
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// API key authentication (Bearer token).
+///
+/// Sends `Authorization: Bearer {key}` by default; the header name and scheme
+/// can be changed for OpenAI-compatible gateways (e.g., `api-key: {key}`).
 #[derive(Debug, Clone)]
 pub struct ApiKeyAuth {
     api_key: String,
+    header_name: String,
+    scheme: Option<String>,
     site_url: Option<String>,
     site_name: Option<String>,
+    project: Option<String>,
 }
 
 impl ApiKeyAuth {
@@ -24,8 +52,82 @@ impl ApiKeyAuth {
     pub fn new(api_key: impl Into<String>) -> Self {
         Self {
             api_key: api_key.into(),
+            header_name: "Authorization".to_string(),
+            scheme: Some("Bearer".to_string()),
             site_url: None,
             site_name: None,
+            project: None,
+        }
+    }
+
+    /// Set the header carrying the API key (defaults to "Authorization").
+    pub fn with_header_name(mut self, name: impl Into<String>) -> Self {
+        self.header_name = name.into();
+        self
+    }
+
+    /// Set the scheme prefixing the API key (defaults to "Bearer").
+    pub fn with_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.scheme = Some(scheme.into());
+        self
+    }
+
+    /// Send the bare API key, without a scheme.
+    pub fn without_scheme(mut self) -> Self {
+        self.scheme = None;
+        self
+    }
+
+    /// Header carrying the API key.
+    fn header_name(&self) -> Result<HeaderName> {
+        HeaderName::from_bytes(self.header_name.as_bytes()).map_err(|_| {
+            OpenRouterError::InvalidRequest(format!(
+                "Invalid auth header name: {}",
+                self.header_name
+            ))
+        })
+    }
+
+    /// All headers to send, failing on any name or value that is not a valid
+    /// header.
+    fn headers(&self) -> Result<Vec<(HeaderName, HeaderValue)>> {
+        let auth_value = self.auth_value();
+        let values = [
+            ("API key", self.header_name()?, Some(auth_value.as_str())),
+            (
+                "site URL",
+                HeaderName::from_static("http-referer"),
+                self.site_url.as_deref(),
+            ),
+            (
+                "site name",
+                HeaderName::from_static("x-title"),
+                self.site_name.as_deref(),
+            ),
+            (
+                "project",
+                HeaderName::from_static("openai-project"),
+                self.project.as_deref(),
+            ),
+        ];
+
+        let mut headers = Vec::new();
+        for (name, header, value) in values {
+            if let Some(value) = value {
+                let value = HeaderValue::from_str(value).map_err(|_| {
+                    OpenRouterError::InvalidRequest(format!("{name} is not a valid header value"))
+                })?;
+                headers.push((header, value));
+            }
+        }
+        Ok(headers)
+    }
+
+    /// Header value carrying the API key.
+    fn auth_value(&self) -> String {
+        match &self.scheme {
+            Some(scheme) => format!("{} {}", scheme, self.api_key),
+            None => self.api_key.clone(),
         }
     }
 
@@ -42,22 +144,61 @@ impl ApiKeyAuth {
         self.site_name = Some(name.into());
         self
     }
+
+    /// Attribute requests to a project (sent as the `OpenAI-Project` header).
+    ///
+    /// OpenRouter itself has no project header and attributes usage per API
+    /// key, so use a separate key per project there; this is for
+    /// OpenAI-compatible gateways that segment usage by project.
+    pub fn with_project(mut self, id: impl Into<String>) -> Self {
+        self.project = Some(id.into());
+        self
+    }
+
+    /// Set the site name and URL only where not already set.
+    ///
+    /// Empty values are ignored. See [`with_cargo_app_info!`](crate::with_cargo_app_info)
+    /// to default them from the calling crate's Cargo metadata.
+    pub fn with_default_app_info(mut self, name: &str, url: &str) -> Self {
+        if self.site_name.is_none() && !name.is_empty() {
+            self.site_name = Some(name.to_string());
+        }
+        if self.site_url.is_none() && !url.is_empty() {
+            self.site_url = Some(url.to_string());
+        }
+        self
+    }
+}
+
+/// Default an [`ApiKeyAuth`]'s `X-Title` and `HTTP-Referer` headers from the
+/// calling crate's `CARGO_PKG_NAME` and `CARGO_PKG_REPOSITORY`.
+///
+/// Values set explicitly before the macro is applied are kept, e.g.
+/// `with_cargo_app_info!(ApiKeyAuth::new(key).with_site_name("My App"))`.
+#[macro_export]
+macro_rules! with_cargo_app_info {
+    ($auth:expr) => {
+        $auth.with_default_app_info(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_REPOSITORY"))
+    };
 }
 
 #[async_trait]
 impl AuthStrategy for ApiKeyAuth {
-    async fn apply(&self, headers: &mut HeaderMap) -> Result<()> {
-        let auth_value = format!("Bearer {}", self.api_key);
-        headers.insert("Authorization", auth_value.parse().unwrap());
-
-        if let Some(url) = &self.site_url {
-            headers.insert("HTTP-Referer", url.parse().unwrap());
+    fn validate(&self) -> Result<()> {
+        if self.api_key.trim().is_empty() {
+            return Err(OpenRouterError::InvalidRequest(
+                "API key must not be empty".to_string(),
+            ));
         }
 
-        if let Some(name) = &self.site_name {
-            headers.insert("X-Title", name.parse().unwrap());
-        }
+        self.headers()?;
+        Ok(())
+    }
 
+    async fn apply(&self, headers: &mut HeaderMap) -> Result<()> {
+        for (name, value) in self.headers()? {
+            headers.insert(name, value);
+        }
         Ok(())
     }
 }
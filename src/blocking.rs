@@ -0,0 +1,53 @@
+//! Synchronous facade over the async client.
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::types::{CreateChatCompletionRequest, CreateChatCompletionResponse, ModelList};
+
+/// Blocking OpenRouter client.
+///
+/// Drives an async [`Client`] on a private single-threaded runtime. Must not
+/// be used from within an async runtime; use [`Client`] there instead.
+pub struct BlockingClient {
+    client: Client,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl BlockingClient {
+    /// Wrap a client.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal runtime cannot be created.
+    pub fn new(client: Client) -> Self {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to build blocking client runtime");
+        Self { client, runtime }
+    }
+
+    /// Create a blocking client configured from the environment (see [`Client::from_env`]).
+    pub fn from_env() -> Result<Self> {
+        Ok(Self::new(Client::from_env()?))
+    }
+
+    /// Get the underlying async client.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Create a chat completion.
+    pub fn create_chat_completion(
+        &self,
+        request: CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionResponse> {
+        self.runtime
+            .block_on(self.client.create_chat_completion(request))
+    }
+
+    /// List available models.
+    pub fn list_models(&self) -> Result<ModelList> {
+        self.runtime.block_on(self.client.list_models())
+    }
+}
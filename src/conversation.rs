@@ -0,0 +1,113 @@
+//! Managed conversation state.
+
+use crate::error::Result;
+use crate::types::{Message, ToolCallOrder};
+
+/// A conversation log that enforces tool-message ordering as it grows.
+///
+/// Applies the rules of [`validate_conversation`](crate::validate_conversation)
+/// to each message as it is pushed: tool results can only be pushed for
+/// outstanding tool calls, and user or assistant turns are rejected while
+/// tool calls are still unanswered.
+#[derive(Debug, Clone, Default)]
+pub struct Conversation {
+    messages: Vec<Message>,
+    order: ToolCallOrder,
+}
+
+impl Conversation {
+    /// Create an empty conversation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a conversation starting with a system prompt.
+    pub fn with_system(content: impl Into<String>) -> Self {
+        Self {
+            messages: vec![Message::system(content)],
+            order: ToolCallOrder::default(),
+        }
+    }
+
+    /// Messages in the conversation.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    /// Tool call IDs that still need a result.
+    pub fn pending_tool_calls(&self) -> &[String] {
+        self.order.pending()
+    }
+
+    /// Append a user message.
+    pub fn push_user(&mut self, content: impl Into<String>) -> Result<()> {
+        self.push(Message::user(content))
+    }
+
+    /// Append an assistant text message.
+    pub fn push_assistant(&mut self, content: impl Into<String>) -> Result<()> {
+        self.push(Message::assistant(content))
+    }
+
+    /// Append a tool result for an outstanding tool call.
+    pub fn push_tool_result(
+        &mut self,
+        tool_call_id: impl Into<String>,
+        content: impl Into<String>,
+    ) -> Result<()> {
+        self.push(Message::tool(tool_call_id, content))
+    }
+
+    /// Append any message (e.g., an assistant response with tool calls).
+    pub fn push(&mut self, message: Message) -> Result<()> {
+        self.order.push(self.messages.len(), &message)?;
+        self.messages.push(message);
+        Ok(())
+    }
+
+    /// Consume the conversation, returning its messages.
+    pub fn into_messages(self) -> Vec<Message> {
+        self.messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CreateChatCompletionRequest, ToolCall};
+
+    #[test]
+    fn test_conversation_enforces_tool_ordering() {
+        let mut conversation = Conversation::with_system("You are helpful.");
+        conversation.push_user("Weather?").unwrap();
+        conversation
+            .push(Message::assistant_with_tool_calls(vec![ToolCall::new(
+                "call_1",
+                "get_weather",
+                "{}",
+            )]))
+            .unwrap();
+        assert!(conversation.push_user("Hello?").is_err());
+        assert!(conversation.push_tool_result("call_2", "Sunny").is_err());
+        conversation.push_tool_result("call_1", "Sunny").unwrap();
+        conversation.push_assistant("It's sunny.").unwrap();
+
+        let request =
+            CreateChatCompletionRequest::from_conversation("openai/gpt-4o", &conversation);
+        assert_eq!(request.messages.len(), 5);
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn test_conversation_agrees_with_validation() {
+        let mut conversation = Conversation::with_system("You are helpful.");
+        conversation.push_assistant("Hi! How can I help?").unwrap();
+        conversation.push_user("Weather?").unwrap();
+        assert!(crate::validate_conversation(conversation.messages()).is_ok());
+
+        // A rejected push leaves the conversation unchanged.
+        assert!(conversation.push_tool_result("call_1", "Sunny").is_err());
+        assert_eq!(conversation.messages().len(), 3);
+        assert!(conversation.pending_tool_calls().is_empty());
+    }
+}
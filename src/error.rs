@@ -10,17 +10,41 @@ pub enum OpenRouterError {
     Request(#[from] reqwest::Error),
 
     /// API returned an error response.
+    ///
+    /// `metadata` carries provider-specific details from `error.metadata`, if any.
     #[error("API error ({status}): {message}")]
-    Api { status: u16, message: String },
+    Api {
+        status: u16,
+        message: String,
+        metadata: Option<serde_json::Value>,
+    },
+
+    /// Error response that did not match the documented error shape
+    /// (only returned with strict error handling enabled).
+    #[error("Unexpected response ({status}): {body}")]
+    UnexpectedResponse { status: u16, body: String },
 
     /// Rate limited by the API.
+    ///
+    /// `reset_at` is the `X-RateLimit-Reset` time (Unix epoch milliseconds), if provided.
     #[error("Rate limited, retry after {retry_after}s")]
-    RateLimited { retry_after: u64 },
+    RateLimited {
+        retry_after: u64,
+        reset_at: Option<u64>,
+    },
 
     /// Authentication failed.
     #[error("Unauthorized: invalid API key")]
     Unauthorized,
 
+    /// API key has been disabled or revoked; a new key must be created.
+    #[error("API key disabled: {0}")]
+    KeyDisabled(String),
+
+    /// Account has been suspended; contact OpenRouter support.
+    #[error("Account suspended: {0}")]
+    AccountSuspended(String),
+
     /// Request was forbidden.
     #[error("Forbidden: {0}")]
     Forbidden(String),
@@ -30,8 +54,39 @@ pub enum OpenRouterError {
     NotFound(String),
 
     /// JSON serialization/deserialization error.
-    #[error("JSON error: {0}")]
-    Json(#[from] serde_json::Error),
+    ///
+    /// `body_snippet` holds a truncated excerpt of the offending response body
+    /// around the error location (empty when no body was involved), and
+    /// `path` the field path to it, such as `data[3].pricing` (empty when
+    /// unknown or at the top level).
+    #[error("JSON error{}: {source}", at_path(.path))]
+    Json {
+        #[source]
+        source: serde_json::Error,
+        body_snippet: String,
+        path: String,
+    },
+
+    /// Response did not have the expected shape or type.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// Generation hit `max_tokens` mid tool call, leaving its arguments as
+    /// invalid JSON. Retry with a higher token budget.
+    #[error("Tool call {name} truncated by max_tokens")]
+    TruncatedToolCall { name: String, arguments: String },
+
+    /// Response body exceeded the configured size limit.
+    #[error("Response exceeded {limit} bytes")]
+    ResponseTooLarge { limit: usize },
+
+    /// Overall deadline passed before the request completed.
+    #[error("Deadline exceeded")]
+    DeadlineExceeded,
+
+    /// Worst-case cost estimate exceeds the configured per-request cap.
+    #[error("Estimated cost {estimated} exceeds limit {limit}")]
+    BudgetExceeded { estimated: f64, limit: f64 },
 
     /// Invalid request parameters.
     #[error("Invalid request: {0}")]
@@ -54,5 +109,213 @@ pub enum OpenRouterError {
     ModelNotAvailable(String),
 }
 
+impl OpenRouterError {
+    /// HTTP status code to report when proxying this error to a caller.
+    ///
+    /// Client-side problems map to 4xx; upstream failures map to 502, 503
+    /// (model unavailable), or 504 (timeouts).
+    pub fn to_http_status(&self) -> u16 {
+        match self {
+            Self::Unauthorized | Self::KeyDisabled(_) | Self::AccountSuspended(_) => 401,
+            Self::InsufficientCredits(_) => 402,
+            Self::Forbidden(_) => 403,
+            Self::NotFound(_) => 404,
+            Self::RateLimited { .. } => 429,
+            Self::InvalidRequest(_)
+            | Self::ContextLengthExceeded(_)
+            | Self::BudgetExceeded { .. } => 400,
+            Self::Api { status, .. } if (400..500).contains(status) => *status,
+            Self::ModelNotAvailable(_) => 503,
+            Self::Request(e) if e.is_timeout() => 504,
+            Self::DeadlineExceeded => 504,
+            _ => 502,
+        }
+    }
+
+    /// Check if the request may succeed when retried.
+    ///
+    /// True for rate limits, server errors, and transient network failures,
+    /// including 5xx and 408 responses whose body is not the documented JSON
+    /// (e.g., HTML error pages from gateways and proxies).
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited { .. } | Self::ServerError(_) => true,
+            Self::Api { status, .. } | Self::UnexpectedResponse { status, .. } => {
+                *status == 408 || *status >= 500
+            }
+            Self::Request(e) => e.is_timeout() || e.is_connect(),
+            _ => false,
+        }
+    }
+}
+
+impl From<serde_json::Error> for OpenRouterError {
+    fn from(source: serde_json::Error) -> Self {
+        Self::Json {
+            source,
+            body_snippet: String::new(),
+            path: String::new(),
+        }
+    }
+}
+
+/// Format a JSON error path for display, if known.
+fn at_path(path: &str) -> String {
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!(" at {path}")
+    }
+}
+
+/// Documented OpenRouter `error.code` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// 400: invalid or missing parameters.
+    BadRequest,
+    /// 401: invalid credentials (expired, disabled, or missing key).
+    InvalidCredentials,
+    /// 402: insufficient credits.
+    InsufficientCredits,
+    /// 403: input flagged by moderation.
+    Moderated,
+    /// 404: model or resource not found.
+    NotFound,
+    /// 408: request timed out.
+    Timeout,
+    /// 429: rate limited.
+    RateLimited,
+    /// 502: chosen model is down or returned an invalid response.
+    ModelDown,
+    /// 503: no provider meets the routing requirements.
+    NoProvider,
+}
+
+impl ErrorCode {
+    /// Parse a numeric `error.code`, returning `None` for undocumented codes.
+    pub fn from_code(code: i32) -> Option<Self> {
+        Some(match code {
+            400 => Self::BadRequest,
+            401 => Self::InvalidCredentials,
+            402 => Self::InsufficientCredits,
+            403 => Self::Moderated,
+            404 => Self::NotFound,
+            408 => Self::Timeout,
+            429 => Self::RateLimited,
+            502 => Self::ModelDown,
+            503 => Self::NoProvider,
+            _ => return None,
+        })
+    }
+
+    /// Numeric value of the code.
+    pub fn code(self) -> i32 {
+        match self {
+            Self::BadRequest => 400,
+            Self::InvalidCredentials => 401,
+            Self::InsufficientCredits => 402,
+            Self::Moderated => 403,
+            Self::NotFound => 404,
+            Self::Timeout => 408,
+            Self::RateLimited => 429,
+            Self::ModelDown => 502,
+            Self::NoProvider => 503,
+        }
+    }
+}
+
 /// Result type alias for OpenRouter operations.
 pub type Result<T> = std::result::Result<T, OpenRouterError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_and_retryability() {
+        let message = || "message".to_string();
+        let cases = [
+            (OpenRouterError::Unauthorized, 401, false),
+            (OpenRouterError::KeyDisabled(message()), 401, false),
+            (OpenRouterError::AccountSuspended(message()), 401, false),
+            (OpenRouterError::InsufficientCredits(message()), 402, false),
+            (OpenRouterError::Forbidden(message()), 403, false),
+            (OpenRouterError::NotFound(message()), 404, false),
+            (
+                OpenRouterError::RateLimited {
+                    retry_after: 1,
+                    reset_at: None,
+                },
+                429,
+                true,
+            ),
+            (OpenRouterError::InvalidRequest(message()), 400, false),
+            (
+                OpenRouterError::ContextLengthExceeded(message()),
+                400,
+                false,
+            ),
+            (
+                OpenRouterError::BudgetExceeded {
+                    estimated: 2.0,
+                    limit: 1.0,
+                },
+                400,
+                false,
+            ),
+            (
+                OpenRouterError::Api {
+                    status: 422,
+                    message: message(),
+                    metadata: None,
+                },
+                422,
+                false,
+            ),
+            (
+                OpenRouterError::Api {
+                    status: 500,
+                    message: message(),
+                    metadata: None,
+                },
+                502,
+                true,
+            ),
+            (
+                OpenRouterError::Api {
+                    status: 408,
+                    message: message(),
+                    metadata: None,
+                },
+                408,
+                true,
+            ),
+            (
+                OpenRouterError::UnexpectedResponse {
+                    status: 503,
+                    body: message(),
+                },
+                502,
+                true,
+            ),
+            (
+                OpenRouterError::UnexpectedResponse {
+                    status: 418,
+                    body: message(),
+                },
+                502,
+                false,
+            ),
+            (OpenRouterError::ModelNotAvailable(message()), 503, false),
+            (OpenRouterError::DeadlineExceeded, 504, false),
+            (OpenRouterError::ServerError(message()), 502, true),
+            (OpenRouterError::InvalidResponse(message()), 502, false),
+            (OpenRouterError::ResponseTooLarge { limit: 1 }, 502, false),
+        ];
+
+        for (error, status, retryable) in cases {
+            assert_eq!(error.to_http_status(), status, "{error:?}");
+            assert_eq!(error.is_retryable(), retryable, "{error:?}");
+        }
+    }
+}
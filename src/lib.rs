@@ -4,11 +4,36 @@
 //! OpenRouter provides access to multiple AI models through a unified OpenAI-compatible API.
 
 mod auth;
+#[cfg(feature = "blocking")]
+mod blocking;
 mod client;
+mod conversation;
 mod error;
+mod log;
+mod reasoning;
+mod retry;
+#[cfg(feature = "tower")]
+mod service;
+mod stream;
+mod tokens;
 mod types;
 
 pub use auth::{ApiKeyAuth, AuthStrategy};
-pub use client::{Client, ClientBuilder};
-pub use error::{OpenRouterError, Result};
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingClient;
+pub use client::{Client, ClientBuilder, API_KEY_ENV_VAR, BASE_URL_ENV_VAR};
+pub use conversation::Conversation;
+pub use error::{ErrorCode, OpenRouterError, Result};
+pub use reasoning::{split_think_tags, split_think_tags_with, THINK_CLOSE_TAG, THINK_OPEN_TAG};
+pub use retry::{Clock, RetryPolicy, SystemClock};
+#[cfg(feature = "tower")]
+pub use service::ChatCompletionService;
+pub use stream::{
+    stream_by_lines, stream_by_sentences, ChatCompletionStream, RawEventStream, SegmentStream,
+    SseEvent, StreamAccumulator, ToolCallAccumulator,
+};
+pub use tokens::{
+    estimate_message_tokens, estimate_tokens, trim_to_budget, trim_to_budget_with,
+    HeuristicTokenCounter, TokenCounter,
+};
 pub use types::*;
@@ -0,0 +1,26 @@
+//! Logging macros that forward to `tracing` when the `tracing` feature is enabled.
+//!
+//! Without the feature the macros expand to nothing, so the dependency can be
+//! dropped entirely.
+
+#[cfg(feature = "tracing")]
+macro_rules! log_debug {
+    ($($arg:tt)*) => { ::tracing::debug!($($arg)*) };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! log_debug {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! log_warn {
+    ($($arg:tt)*) => { ::tracing::warn!($($arg)*) };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! log_warn {
+    ($($arg:tt)*) => {};
+}
+
+pub(crate) use {log_debug as debug, log_warn as warn};
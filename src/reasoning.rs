@@ -0,0 +1,78 @@
+//! Extraction of inline reasoning from message content.
+
+/// Default opening tag of an inline reasoning block.
+pub const THINK_OPEN_TAG: &str = "<think>";
+/// Default closing tag of an inline reasoning block.
+pub const THINK_CLOSE_TAG: &str = "</think>";
+
+/// Split `<think>...</think>` blocks out of message content.
+///
+/// Some models without a native `reasoning` field emit their thinking inline.
+/// Returns the reasoning, if any block was found, and the answer with the
+/// blocks removed (see [`split_think_tags_with`] for other tags).
+pub fn split_think_tags(content: &str) -> (Option<String>, String) {
+    split_think_tags_with(content, THINK_OPEN_TAG, THINK_CLOSE_TAG)
+}
+
+/// Split reasoning blocks delimited by `open` and `close` out of message
+/// content (see [`split_think_tags`]).
+///
+/// Multiple blocks are joined with newlines. An unclosed block, as left by a
+/// generation cut off mid-thought, runs to the end of the content. The answer
+/// is always trimmed; empty tags never match, leaving all content as answer.
+pub fn split_think_tags_with(content: &str, open: &str, close: &str) -> (Option<String>, String) {
+    if open.is_empty() || close.is_empty() {
+        return (None, content.trim().to_string());
+    }
+
+    let mut reasoning = Vec::new();
+    let mut answer = String::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find(open) {
+        answer.push_str(&rest[..start]);
+        let inner = &rest[start + open.len()..];
+        let (thought, after) = match inner.find(close) {
+            Some(end) => (&inner[..end], &inner[end + close.len()..]),
+            None => (inner, ""),
+        };
+        reasoning.push(thought.trim());
+        rest = after;
+    }
+    if reasoning.is_empty() {
+        return (None, content.trim().to_string());
+    }
+    answer.push_str(rest);
+
+    (Some(reasoning.join("\n")), answer.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_think_tags() {
+        let (reasoning, answer) =
+            split_think_tags("<think>\n2 plus 2 is 4.\n</think>\n\nThe answer is 4.");
+        assert_eq!(reasoning.as_deref(), Some("2 plus 2 is 4."));
+        assert_eq!(answer, "The answer is 4.");
+
+        let (reasoning, answer) = split_think_tags("Just an answer.\n");
+        assert_eq!(reasoning, None);
+        assert_eq!(answer, "Just an answer.");
+
+        let (reasoning, answer) = split_think_tags_with("[r]cut off", "[r]", "[/r]");
+        assert_eq!(reasoning.as_deref(), Some("cut off"));
+        assert_eq!(answer, "");
+    }
+
+    #[test]
+    fn test_split_think_tags_empty_tags() {
+        for (open, close) in [("", "</think>"), ("<think>", ""), ("", "")] {
+            let (reasoning, answer) = split_think_tags_with(" <think>a</think> b ", open, close);
+            assert_eq!(reasoning, None);
+            assert_eq!(answer, "<think>a</think> b");
+        }
+    }
+}
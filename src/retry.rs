@@ -0,0 +1,178 @@
+//! Retry policy for transient failures.
+
+use crate::error::OpenRouterError;
+use async_trait::async_trait;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Source of time for retry and rate limit backoff.
+///
+/// The default [`SystemClock`] uses the system time and `tokio::time`; tests
+/// can inject a clock that advances instantly.
+#[async_trait]
+pub trait Clock: Send + Sync {
+    /// Current wall-clock time.
+    fn now(&self) -> SystemTime;
+
+    /// Sleep for the given duration.
+    async fn sleep(&self, duration: Duration);
+}
+
+/// Clock backed by the system time and `tokio::time::sleep`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// Retry policy with exponential backoff.
+///
+/// Rate-limited requests wait until the `X-RateLimit-Reset` time when the API
+/// provides one, falling back to `retry-after`. Server errors and transient
+/// network failures use exponential backoff.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the initial attempt.
+    pub max_retries: u32,
+    /// Backoff before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for exponential backoff.
+    pub max_backoff: Duration,
+    /// Upper bound for waits derived from rate limit headers.
+    pub max_rate_limit_wait: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_rate_limit_wait: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a retry policy with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of retries.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the backoff before the first retry.
+    pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Set the upper bound for exponential backoff.
+    pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Set the upper bound for waits derived from rate limit headers.
+    pub fn with_max_rate_limit_wait(mut self, wait: Duration) -> Self {
+        self.max_rate_limit_wait = wait;
+        self
+    }
+
+    /// Compute the delay before retrying after `error`.
+    ///
+    /// `attempt` is the number of retries already performed. Returns `None`
+    /// if the error is not retryable or the retry budget is exhausted.
+    pub fn delay_for(&self, attempt: u32, error: &OpenRouterError) -> Option<Duration> {
+        self.delay_at(attempt, error, SystemClock.now())
+    }
+
+    /// Compute the retry delay relative to the given current time.
+    pub(crate) fn delay_at(
+        &self,
+        attempt: u32,
+        error: &OpenRouterError,
+        now: SystemTime,
+    ) -> Option<Duration> {
+        if attempt >= self.max_retries || !error.is_retryable() {
+            return None;
+        }
+
+        match error {
+            OpenRouterError::RateLimited {
+                retry_after,
+                reset_at,
+            } => {
+                let wait = reset_at
+                    .map(|reset| Duration::from_millis(reset.saturating_sub(epoch_millis(now))))
+                    .unwrap_or_else(|| Duration::from_secs(*retry_after));
+                Some(wait.min(self.max_rate_limit_wait))
+            }
+            _ => Some(self.backoff(attempt)),
+        }
+    }
+
+    /// Exponential backoff for the given attempt.
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+fn epoch_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now_millis() -> u64 {
+        epoch_millis(SystemTime::now())
+    }
+
+    #[test]
+    fn test_rate_limit_prefers_reset() {
+        let policy = RetryPolicy::new().with_max_rate_limit_wait(Duration::from_secs(10));
+
+        let error = OpenRouterError::RateLimited {
+            retry_after: 60,
+            reset_at: Some(now_millis() + 2_000),
+        };
+        let delay = policy.delay_for(0, &error).unwrap();
+        assert!(delay <= Duration::from_secs(2) && delay > Duration::from_secs(1));
+
+        let error = OpenRouterError::RateLimited {
+            retry_after: 60,
+            reset_at: Some(now_millis() + 120_000),
+        };
+        assert_eq!(policy.delay_for(0, &error), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_backoff_exhausts() {
+        let policy = RetryPolicy::new().with_max_retries(2);
+        let error = OpenRouterError::ServerError("boom".to_string());
+
+        assert_eq!(
+            policy.delay_for(0, &error),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(policy.delay_for(1, &error), Some(Duration::from_secs(1)));
+        assert_eq!(policy.delay_for(2, &error), None);
+    }
+}
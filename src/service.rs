@@ -0,0 +1,49 @@
+//! `tower::Service` adapter for composing the client with tower middleware.
+
+use crate::client::Client;
+use crate::error::{OpenRouterError, Result};
+use crate::types::{CreateChatCompletionRequest, CreateChatCompletionResponse};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Chat completion [`tower_service::Service`] backed by a [`Client`].
+///
+/// Cloning is cheap; clones share the underlying client.
+#[derive(Clone)]
+pub struct ChatCompletionService {
+    client: Arc<Client>,
+}
+
+impl ChatCompletionService {
+    /// Wrap a client.
+    pub fn new(client: Client) -> Self {
+        Self::from_arc(Arc::new(client))
+    }
+
+    /// Wrap a shared client.
+    pub fn from_arc(client: Arc<Client>) -> Self {
+        Self { client }
+    }
+
+    /// Get the underlying client.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+impl tower_service::Service<CreateChatCompletionRequest> for ChatCompletionService {
+    type Response = CreateChatCompletionResponse;
+    type Error = OpenRouterError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: CreateChatCompletionRequest) -> Self::Future {
+        let client = self.client.clone();
+        Box::pin(async move { client.create_chat_completion(request).await })
+    }
+}
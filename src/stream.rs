@@ -0,0 +1,908 @@
+//! Streaming support for chat completions (server-sent events).
+
+use crate::client::parse_json;
+use crate::error::{OpenRouterError, Result};
+use crate::types::{
+    Annotation, ChatCompletionChunk, Message, MessageContent, Role, ToolCall, ToolCallDelta, Usage,
+};
+use bytes::Bytes;
+use futures_core::Stream;
+use std::collections::{BTreeMap, VecDeque};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+/// Sentinel data payload marking the end of a stream.
+const DONE: &str = "[DONE]";
+
+type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
+
+/// A stream of chat completion chunks.
+///
+/// The HTTP body is read lazily: a network read only happens when the consumer
+/// polls for the next chunk and no decoded chunk is pending. No background task
+/// is spawned, so a slow consumer applies backpressure through the connection.
+/// Memory use is bounded by one network read plus any partial SSE frame.
+///
+/// Named events other than `message` and keep-alive chunks (see
+/// [`ChatCompletionChunk::is_keep_alive`]) are skipped; use [`RawEventStream`]
+/// to observe them.
+pub struct ChatCompletionStream {
+    events: RawEventStream,
+}
+
+impl ChatCompletionStream {
+    /// Create a chunk stream over a raw SSE byte stream.
+    pub(crate) fn new<S>(inner: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes>> + Send + 'static,
+    {
+        Self {
+            events: RawEventStream::new(inner),
+        }
+    }
+
+    /// Create a chunk stream reading from an HTTP response body.
+    pub(crate) fn from_response(response: reqwest::Response) -> Self {
+        Self::new(response_body(response))
+    }
+
+    /// ID of the last event received (see [`RawEventStream::last_event_id`]).
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.events.last_event_id()
+    }
+
+    /// Drive the stream, sending each chunk or error into `tx`.
+    ///
+    /// Returns when the stream ends or the receiver is dropped; the channel
+    /// closing once `tx` is dropped signals completion to the receiver.
+    pub async fn forward_into(mut self, tx: mpsc::Sender<Result<ChatCompletionChunk>>) {
+        use futures_util::StreamExt;
+
+        while let Some(item) = self.next().await {
+            if tx.send(item).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl Stream for ChatCompletionStream {
+    type Item = Result<ChatCompletionChunk>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            let event = match Pin::new(&mut this.events).poll_next(cx) {
+                Poll::Ready(Some(Ok(event))) => event,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            if event.event.as_deref().is_some_and(|name| name != "message") {
+                continue;
+            }
+            if event.data == DONE {
+                this.events.close();
+                return Poll::Ready(None);
+            }
+
+            let chunk = parse_json::<ChatCompletionChunk>(&event.data)
+                .and_then(|chunk| chunk.validate().map(|()| chunk));
+            if chunk.as_ref().is_ok_and(ChatCompletionChunk::is_keep_alive) {
+                continue;
+            }
+            return Poll::Ready(Some(chunk));
+        }
+    }
+}
+
+/// Boundary at which [`SegmentStream`] emits buffered content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segmentation {
+    Lines,
+    Sentences,
+}
+
+/// A stream of complete lines or sentences of streamed content.
+///
+/// Created by [`stream_by_lines`] or [`stream_by_sentences`]. Content deltas
+/// of the first choice are buffered until a boundary is seen; any trailing
+/// text is emitted when the underlying stream ends.
+pub struct SegmentStream {
+    inner: ChatCompletionStream,
+    segmentation: Segmentation,
+    buffer: String,
+    finished: bool,
+}
+
+/// Emit streamed content one line at a time, without the line terminator.
+pub fn stream_by_lines(stream: ChatCompletionStream) -> SegmentStream {
+    SegmentStream::new(stream, Segmentation::Lines)
+}
+
+/// Emit streamed content one sentence at a time.
+///
+/// A sentence ends at `.`, `!`, or `?` followed by whitespace, or at a
+/// newline. Sentences are trimmed of surrounding whitespace.
+pub fn stream_by_sentences(stream: ChatCompletionStream) -> SegmentStream {
+    SegmentStream::new(stream, Segmentation::Sentences)
+}
+
+impl SegmentStream {
+    fn new(inner: ChatCompletionStream, segmentation: Segmentation) -> Self {
+        Self {
+            inner,
+            segmentation,
+            buffer: String::new(),
+            finished: false,
+        }
+    }
+
+    /// Split the next complete segment off the buffer.
+    fn next_segment(&mut self) -> Option<String> {
+        loop {
+            let (end, skip) = match self.segmentation {
+                Segmentation::Lines => (self.buffer.find('\n')?, 1),
+                Segmentation::Sentences => sentence_end(&self.buffer)?,
+            };
+            let segment = self.buffer[..end].to_string();
+            self.buffer.drain(..end + skip);
+            let segment = match self.segmentation {
+                Segmentation::Lines => segment.strip_suffix('\r').unwrap_or(&segment).to_string(),
+                Segmentation::Sentences => segment.trim().to_string(),
+            };
+            if self.segmentation == Segmentation::Lines || !segment.is_empty() {
+                return Some(segment);
+            }
+        }
+    }
+
+    /// Take whatever remains in the buffer once the stream has ended.
+    fn flush(&mut self) -> Option<String> {
+        let rest = std::mem::take(&mut self.buffer);
+        let rest = match self.segmentation {
+            Segmentation::Lines => rest,
+            Segmentation::Sentences => rest.trim().to_string(),
+        };
+        (!rest.is_empty()).then_some(rest)
+    }
+}
+
+/// Find the end of the first sentence as `(end, separator_len)`, where `end`
+/// is the byte length of the sentence including its punctuation.
+fn sentence_end(text: &str) -> Option<(usize, usize)> {
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '\n' {
+            return Some((i, 1));
+        }
+        if matches!(c, '.' | '!' | '?') {
+            if let Some(&(j, next)) = chars.peek() {
+                if next.is_whitespace() {
+                    return Some((j, next.len_utf8()));
+                }
+            }
+        }
+    }
+    None
+}
+
+impl Stream for SegmentStream {
+    type Item = Result<String>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            if let Some(segment) = this.next_segment() {
+                return Poll::Ready(Some(Ok(segment)));
+            }
+            if this.finished {
+                return Poll::Ready(this.flush().map(Ok));
+            }
+
+            match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(chunk))) => {
+                    let content = chunk
+                        .choices
+                        .iter()
+                        .filter(|c| c.index == 0)
+                        .filter_map(|c| c.delta.content.as_deref());
+                    this.buffer.extend(content);
+                }
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => this.finished = true,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// A stream of raw server-sent events.
+///
+/// Lower-level than [`ChatCompletionStream`]: every dispatched event is yielded
+/// as-is, including named events and the `[DONE]` sentinel, so callers can
+/// handle payloads the typed layer does not model yet.
+pub struct RawEventStream {
+    inner: ByteStream,
+    decoder: SseDecoder,
+    pending: VecDeque<SseEvent>,
+    finished: bool,
+}
+
+impl RawEventStream {
+    /// Create an event stream over a raw SSE byte stream.
+    pub(crate) fn new<S>(inner: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes>> + Send + 'static,
+    {
+        Self {
+            inner: Box::pin(inner),
+            decoder: SseDecoder::default(),
+            pending: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// Create an event stream reading from an HTTP response body.
+    pub(crate) fn from_response(response: reqwest::Response) -> Self {
+        Self::new(response_body(response))
+    }
+
+    /// ID of the last event received, as set by the most recent `id:` field.
+    ///
+    /// Send it as the `Last-Event-ID` header to resume a dropped stream where
+    /// the server supports it.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.decoder.last_event_id()
+    }
+
+    /// Stop yielding events, discarding any that are pending.
+    fn close(&mut self) {
+        self.finished = true;
+        self.pending.clear();
+    }
+}
+
+impl Stream for RawEventStream {
+    type Item = Result<SseEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            if let Some(event) = this.pending.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            if this.finished {
+                return Poll::Ready(None);
+            }
+
+            match this.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(bytes))) => this.pending.extend(this.decoder.push(&bytes)),
+                Poll::Ready(Some(Err(e))) => {
+                    this.finished = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+                Poll::Ready(None) => {
+                    this.finished = true;
+                    this.pending.extend(this.decoder.finish());
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Read an HTTP response body lazily, one network chunk per poll.
+pub(crate) fn response_body(
+    response: reqwest::Response,
+) -> impl Stream<Item = Result<Bytes>> + Send {
+    futures_util::stream::unfold(Some(response), |response| async move {
+        let mut response = response?;
+        match response.chunk().await {
+            Ok(Some(bytes)) => Some((Ok(bytes), Some(response))),
+            Ok(None) => None,
+            Err(e) => Some((Err(OpenRouterError::from(e)), None)),
+        }
+    })
+}
+
+/// A decoded server-sent event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// Event name (`event:` field).
+    pub event: Option<String>,
+    /// Event payload (`data:` fields joined by newlines).
+    pub data: String,
+    /// Event ID (`id:` field).
+    pub id: Option<String>,
+}
+
+/// Incremental decoder for `text/event-stream` bodies.
+///
+/// A frame that repeats the previous event's `id:` and payload, as proxies
+/// replaying the last event on reconnect send, is dropped.
+#[derive(Debug, Default)]
+pub(crate) struct SseDecoder {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+    id: Option<String>,
+    /// Whether the frame being decoded has its own `id:` field.
+    frame_has_id: bool,
+    last: Option<SseEvent>,
+}
+
+impl SseDecoder {
+    /// Feed bytes into the decoder, returning every event completed by them.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let mut line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if let Some(event) = self.process_line(&String::from_utf8_lossy(&line)) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Flush any trailing event not terminated by a blank line.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).into_owned();
+            if let Some(event) = self.process_line(&line) {
+                return Some(event);
+            }
+        }
+        self.dispatch()
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+
+        // Comment lines (e.g. ": OPENROUTER PROCESSING") keep the connection alive.
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "data" => self.data.push(value.to_string()),
+            "event" => self.event = Some(value.to_string()),
+            "id" => {
+                self.id = Some(value.to_string());
+                self.frame_has_id = true;
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// ID of the last event, as set by the most recent `id:` field.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = self.event.take();
+        let frame_has_id = std::mem::take(&mut self.frame_has_id);
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let event = SseEvent {
+            event,
+            data: std::mem::take(&mut self.data).join("\n"),
+            id: self.id.clone(),
+        };
+        if frame_has_id && self.last.as_ref() == Some(&event) {
+            return None;
+        }
+        self.last = Some(event.clone());
+        Some(event)
+    }
+}
+
+/// Accumulates streamed chunks into a complete message.
+///
+/// Only the first choice (index 0) is accumulated. Content and reasoning are
+/// kept separate so reasoning can be displayed independently of the answer.
+#[derive(Debug, Clone, Default)]
+pub struct StreamAccumulator {
+    id: Option<String>,
+    model: Option<String>,
+    content: String,
+    reasoning: String,
+    refusal: String,
+    annotations: Vec<Annotation>,
+    tool_calls: ToolCallAccumulator,
+    finish_reason: Option<String>,
+    usage: Option<Usage>,
+}
+
+impl StreamAccumulator {
+    /// Create an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a chunk to the accumulated state.
+    ///
+    /// Usage is recorded even from chunks without choices, since some
+    /// providers report it in a separate final event.
+    pub fn push(&mut self, chunk: &ChatCompletionChunk) {
+        if self.id.is_none() && !chunk.id.is_empty() {
+            self.id = Some(chunk.id.clone());
+        }
+        if self.model.is_none() && !chunk.model.is_empty() {
+            self.model = Some(chunk.model.clone());
+        }
+        if let Some(usage) = &chunk.usage {
+            self.usage = Some(usage.clone());
+        }
+
+        for choice in chunk.choices.iter().filter(|c| c.index == 0) {
+            let delta = &choice.delta;
+            if let Some(content) = &delta.content {
+                self.content.push_str(content);
+            }
+            if let Some(reasoning) = &delta.reasoning {
+                self.reasoning.push_str(reasoning);
+            }
+            if let Some(refusal) = &delta.refusal {
+                self.refusal.push_str(refusal);
+            }
+            for annotation in delta.annotations.iter().flatten() {
+                if !self
+                    .annotations
+                    .iter()
+                    .any(|a| same_annotation(a, annotation))
+                {
+                    self.annotations.push(annotation.clone());
+                }
+            }
+            for tool_call in delta.tool_calls.iter().flatten() {
+                self.tool_calls.push(tool_call);
+            }
+            if let Some(reason) = &choice.finish_reason {
+                self.finish_reason = Some(reason.clone());
+            }
+        }
+    }
+
+    /// Response ID.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Model used.
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// Accumulated answer content.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Accumulated reasoning.
+    pub fn reasoning(&self) -> &str {
+        &self.reasoning
+    }
+
+    /// Accumulated refusal, if the model declined.
+    pub fn refusal(&self) -> Option<&str> {
+        (!self.refusal.is_empty()).then_some(self.refusal.as_str())
+    }
+
+    /// Annotations received so far, without duplicates.
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Finish reason, once the stream has completed.
+    pub fn finish_reason(&self) -> Option<&str> {
+        self.finish_reason.as_deref()
+    }
+
+    /// Token usage, if reported.
+    pub fn usage(&self) -> Option<&Usage> {
+        self.usage.as_ref()
+    }
+
+    /// Assembled tool calls.
+    pub fn tool_calls(&self) -> Vec<ToolCall> {
+        self.tool_calls.tool_calls()
+    }
+
+    /// Convert the accumulated state into an assistant message.
+    pub fn into_message(self) -> Message {
+        let tool_calls = self.tool_calls.tool_calls();
+        Message {
+            role: Role::Assistant,
+            content: (!self.content.is_empty()).then_some(MessageContent::Text(self.content)),
+            name: None,
+            tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+            tool_call_id: None,
+            refusal: (!self.refusal.is_empty()).then_some(self.refusal),
+            audio: None,
+            annotations: (!self.annotations.is_empty()).then_some(self.annotations),
+        }
+    }
+}
+
+/// Whether two annotations are the same: a URL citation re-sent by a later
+/// chunk has the same type, URL, and span; other annotations must match
+/// exactly.
+fn same_annotation(a: &Annotation, b: &Annotation) -> bool {
+    match (&a.url_citation, &b.url_citation) {
+        (Some(x), Some(y)) => {
+            a.annotation_type == b.annotation_type
+                && (&x.url, x.start_index, x.end_index) == (&y.url, y.start_index, y.end_index)
+        }
+        _ => a == b,
+    }
+}
+
+/// Assembles streamed tool call deltas into complete tool calls.
+///
+/// Partial calls are keyed by the delta `index`, so argument fragments of
+/// parallel calls are never interleaved regardless of arrival order.
+#[derive(Debug, Clone, Default)]
+pub struct ToolCallAccumulator {
+    calls: BTreeMap<usize, PartialToolCall>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PartialToolCall {
+    id: String,
+    tool_type: Option<String>,
+    name: String,
+    arguments: String,
+}
+
+impl ToolCallAccumulator {
+    /// Create an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a tool call delta.
+    pub fn push(&mut self, delta: &ToolCallDelta) {
+        let call = self.calls.entry(delta.index).or_default();
+        if let Some(id) = &delta.id {
+            call.id.clone_from(id);
+        }
+        if let Some(tool_type) = &delta.tool_type {
+            call.tool_type = Some(tool_type.clone());
+        }
+        if let Some(function) = &delta.function {
+            if let Some(name) = &function.name {
+                call.name.push_str(name);
+            }
+            if let Some(arguments) = &function.arguments {
+                call.arguments.push_str(arguments);
+            }
+        }
+    }
+
+    /// Assembled tool calls, ordered by index.
+    pub fn tool_calls(&self) -> Vec<ToolCall> {
+        self.calls
+            .values()
+            .map(|call| {
+                let mut tool_call = ToolCall::new(&call.id, &call.name, &call.arguments);
+                if let Some(tool_type) = &call.tool_type {
+                    tool_call.tool_type.clone_from(tool_type);
+                }
+                tool_call
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_decoder_split_frames() {
+        let mut decoder = SseDecoder::default();
+        assert!(decoder
+            .push(b": OPENROUTER PROCESSING\n\ndata: {\"a\"")
+            .is_empty());
+
+        let events = decoder.push(b":1}\r\n\r\ndata: [DONE]\n\n");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].data, "{\"a\":1}");
+        assert_eq!(events[1].data, DONE);
+    }
+
+    #[test]
+    fn test_sse_decoder_drops_replayed_frame() {
+        let mut decoder = SseDecoder::default();
+        let events = decoder.push(b"id: 1\ndata: a\n\nid: 1\ndata: a\n\ndata: a\n\n");
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].data, "a");
+        assert_eq!(decoder.last_event_id(), Some("1"));
+    }
+
+    #[test]
+    fn test_named_events_skipped_by_typed_stream() {
+        use futures_util::{FutureExt, StreamExt};
+
+        let transcript = concat!(
+            "event: ping\ndata: {}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"a\"}}]}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let body = || futures_util::stream::iter([Ok(Bytes::from_static(transcript.as_bytes()))]);
+
+        let events: Vec<_> = RawEventStream::new(body())
+            .collect::<Vec<_>>()
+            .now_or_never()
+            .unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].as_ref().unwrap().event.as_deref(), Some("ping"));
+
+        let chunks: Vec<_> = ChatCompletionStream::new(body())
+            .collect::<Vec<_>>()
+            .now_or_never()
+            .unwrap();
+        assert_eq!(chunks.len(), 1);
+    }
+
+    #[test]
+    fn test_keep_alive_chunks_skipped() {
+        use futures_util::{FutureExt, StreamExt};
+
+        let transcript = concat!(
+            "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+            "data: {\"choices\":[]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"}}]}\n\n",
+            "data: {\"id\":\"gen-1\",\"choices\":[]}\n\n",
+            "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":1,\"completion_tokens\":1,\"total_tokens\":2}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let body = futures_util::stream::iter([Ok(Bytes::from_static(transcript.as_bytes()))]);
+
+        let chunks: Vec<_> = ChatCompletionStream::new(body)
+            .collect::<Vec<_>>()
+            .now_or_never()
+            .unwrap();
+        assert_eq!(chunks.len(), 4);
+
+        let mut acc = StreamAccumulator::new();
+        for chunk in &chunks {
+            acc.push(chunk.as_ref().unwrap());
+        }
+        assert_eq!(acc.content(), "Hi");
+        assert_eq!(acc.finish_reason(), Some("stop"));
+        assert_eq!(acc.usage().map(|u| u.total_tokens), Some(2));
+    }
+
+    #[test]
+    fn test_malformed_chunk_reports_path() {
+        use futures_util::{FutureExt, StreamExt};
+
+        let transcript = "data: {\"choices\":[{\"index\":\"zero\"}]}\n\n";
+        let body = futures_util::stream::iter([Ok(Bytes::from_static(transcript.as_bytes()))]);
+
+        let chunks: Vec<_> = ChatCompletionStream::new(body)
+            .collect::<Vec<_>>()
+            .now_or_never()
+            .unwrap();
+        match &chunks[0] {
+            Err(OpenRouterError::Json { path, .. }) => assert_eq!(path, "choices[0].index"),
+            other => panic!("expected JSON error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_forward_into_channel() {
+        use futures_util::FutureExt;
+
+        let transcript = concat!(
+            "data: {\"choices\":[{\"delta\":{\"content\":\"a\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"b\"}}]}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let body = futures_util::stream::iter([Ok(Bytes::from_static(transcript.as_bytes()))]);
+        let (tx, mut rx) = mpsc::channel(4);
+
+        ChatCompletionStream::new(body)
+            .forward_into(tx)
+            .now_or_never()
+            .unwrap();
+
+        let mut content = String::new();
+        while let Ok(chunk) = rx.try_recv() {
+            content.push_str(chunk.unwrap().choices[0].delta.content.as_deref().unwrap());
+        }
+        assert_eq!(content, "ab");
+        assert!(rx.is_closed());
+    }
+
+    #[test]
+    fn test_stream_by_sentences() {
+        use futures_util::{FutureExt, StreamExt};
+
+        let transcript = concat!(
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Pi is 3.14. Is\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\" it? Yes\\nDone\"}}]}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let body = futures_util::stream::iter([Ok(Bytes::from_static(transcript.as_bytes()))]);
+        let sentences: Vec<_> = stream_by_sentences(ChatCompletionStream::new(body))
+            .map(|s| s.unwrap())
+            .collect::<Vec<_>>()
+            .now_or_never()
+            .unwrap();
+
+        assert_eq!(sentences, ["Pi is 3.14.", "Is it?", "Yes", "Done"]);
+    }
+
+    #[test]
+    fn test_stream_reads_lazily() {
+        use futures_util::{FutureExt, StreamExt};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let reads = Arc::new(AtomicUsize::new(0));
+        let frames = [
+            "data: {\"choices\":[{\"delta\":{\"content\":\"a\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"b\"}}]}\n\n",
+            "data: [DONE]\n\n",
+        ];
+        let counter = reads.clone();
+        let body = futures_util::stream::iter(frames).map(move |frame| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(Bytes::from_static(frame.as_bytes()))
+        });
+
+        let mut stream = ChatCompletionStream::new(body);
+        assert_eq!(reads.load(Ordering::SeqCst), 0);
+
+        let chunk = stream.next().now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(chunk.choices[0].delta.content.as_deref(), Some("a"));
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
+
+        assert!(stream.next().now_or_never().unwrap().is_some());
+        assert!(stream.next().now_or_never().unwrap().is_none());
+        assert_eq!(reads.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_tool_calls_keyed_by_index() {
+        let deltas = [
+            r#"{"index":0,"id":"call_a","type":"function","function":{"name":"get_weather","arguments":""}}"#,
+            r#"{"index":1,"id":"call_b","type":"function","function":{"name":"get_time","arguments":""}}"#,
+            r#"{"index":0,"function":{"arguments":"{\"city\":"}}"#,
+            r#"{"index":1,"function":{"arguments":"{\"tz\":"}}"#,
+            r#"{"index":1,"function":{"arguments":"\"UTC\"}"}}"#,
+            r#"{"index":0,"function":{"arguments":"\"Paris\"}"}}"#,
+        ];
+
+        let mut acc = ToolCallAccumulator::new();
+        for delta in deltas {
+            acc.push(&serde_json::from_str(delta).unwrap());
+        }
+
+        let calls = acc.tool_calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].id, "call_a");
+        assert_eq!(calls[0].function.name, "get_weather");
+        assert_eq!(calls[0].function.arguments, r#"{"city":"Paris"}"#);
+        assert_eq!(calls[1].id, "call_b");
+        assert_eq!(calls[1].function.name, "get_time");
+        assert_eq!(calls[1].function.arguments, r#"{"tz":"UTC"}"#);
+    }
+
+    #[test]
+    fn test_usage_only_final_chunk() {
+        use futures_util::{FutureExt, StreamExt};
+
+        let transcript = concat!(
+            "data: {\"id\":\"gen-1\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hi\"},\"finish_reason\":\"stop\"}]}\n\n",
+            "data: {\"id\":\"gen-1\",\"choices\":[],\"usage\":{\"prompt_tokens\":3,\"completion_tokens\":1,\"total_tokens\":4}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let body = futures_util::stream::iter([Ok(Bytes::from_static(transcript.as_bytes()))]);
+        let chunks: Vec<_> = ChatCompletionStream::new(body)
+            .collect::<Vec<_>>()
+            .now_or_never()
+            .unwrap();
+
+        let mut acc = StreamAccumulator::new();
+        for chunk in chunks {
+            acc.push(&chunk.unwrap());
+        }
+
+        assert_eq!(acc.content(), "Hi");
+        assert_eq!(acc.usage().map(|u| u.total_tokens), Some(4));
+    }
+
+    #[test]
+    fn test_accumulator_refusal_and_annotations() {
+        let citation = r#"{"type":"url_citation","url_citation":{"url":"https://example.com","title":"Example","start_index":0,"end_index":5}}"#;
+        let chunks = [
+            r#"{"id":"gen-1","model":"m","choices":[{"index":0,"delta":{"role":"assistant","refusal":"I can't"}}]}"#.to_string(),
+            r#"{"id":"gen-1","model":"m","choices":[{"index":0,"delta":{"refusal":" help with that."}}]}"#.to_string(),
+            format!(r#"{{"id":"gen-1","model":"m","choices":[{{"index":0,"delta":{{"content":"Hello","annotations":[{citation}]}}}}]}}"#),
+            format!(r#"{{"id":"gen-1","model":"m","choices":[{{"index":0,"delta":{{"annotations":[{citation}]}},"finish_reason":"stop"}}]}}"#),
+        ];
+
+        let mut acc = StreamAccumulator::new();
+        for chunk in &chunks {
+            acc.push(&serde_json::from_str(chunk).unwrap());
+        }
+
+        assert_eq!(acc.refusal(), Some("I can't help with that."));
+        assert_eq!(acc.annotations().len(), 1);
+        let message = acc.into_message();
+        assert_eq!(
+            message.annotations.unwrap()[0]
+                .url_citation
+                .as_ref()
+                .unwrap()
+                .url,
+            "https://example.com"
+        );
+    }
+
+    #[test]
+    fn test_accumulator_keeps_distinct_non_citation_annotations() {
+        let file = |name: &str| {
+            format!(
+                r#"{{"id":"gen-1","model":"m","choices":[{{"index":0,"delta":{{"annotations":[{{"type":"file","file":{{"filename":"{name}"}}}}]}}}}]}}"#
+            )
+        };
+        let chunks = [file("a.pdf"), file("b.pdf"), file("a.pdf")];
+
+        let mut acc = StreamAccumulator::new();
+        for chunk in &chunks {
+            acc.push(&serde_json::from_str(chunk).unwrap());
+        }
+
+        let annotations = acc.annotations();
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations[1].extra["file"]["filename"], "b.pdf");
+    }
+
+    #[test]
+    fn test_accumulator_separates_reasoning() {
+        let chunks = [
+            r#"{"id":"gen-1","model":"m","choices":[{"index":0,"delta":{"role":"assistant","reasoning":"Think"}}]}"#,
+            r#"{"id":"gen-1","model":"m","choices":[{"index":0,"delta":{"reasoning":"ing..."}}]}"#,
+            r#"{"id":"gen-1","model":"m","choices":[{"index":0,"delta":{"content":"Hi"},"finish_reason":"stop"}]}"#,
+        ];
+
+        let mut acc = StreamAccumulator::new();
+        for chunk in chunks {
+            acc.push(&serde_json::from_str(chunk).unwrap());
+        }
+
+        assert_eq!(acc.reasoning(), "Thinking...");
+        assert_eq!(acc.content(), "Hi");
+        assert_eq!(acc.finish_reason(), Some("stop"));
+    }
+}
@@ -0,0 +1,185 @@
+//! Token estimation and history trimming.
+
+use crate::types::{ContentPart, Message, MessageContent, Role};
+
+/// Approximate number of characters per token for English text.
+const CHARS_PER_TOKEN: usize = 4;
+/// Fixed per-message overhead (role and framing tokens).
+const MESSAGE_OVERHEAD: usize = 4;
+/// Flat estimate for an image or audio part.
+const MEDIA_PART_TOKENS: usize = 85;
+
+/// Counts tokens for budgeting and cost estimation.
+///
+/// The default [`HeuristicTokenCounter`] is approximate; implement this trait
+/// over a model's real tokenizer for exact counts.
+pub trait TokenCounter: Send + Sync {
+    /// Count the tokens of a piece of text.
+    fn count(&self, text: &str) -> usize;
+
+    /// Count the tokens of a list of messages, including per-message overhead.
+    ///
+    /// Defaults to counting text content and tool calls with
+    /// [`count`](Self::count), plus flat estimates for framing and media parts.
+    fn count_messages(&self, messages: &[Message]) -> usize {
+        messages
+            .iter()
+            .map(|message| {
+                let content = match &message.content {
+                    Some(MessageContent::Text(text)) => self.count(text),
+                    Some(MessageContent::Parts(parts)) => parts
+                        .iter()
+                        .map(|part| match part {
+                            ContentPart::Text { text } => self.count(text),
+                            ContentPart::ImageUrl { .. } | ContentPart::InputAudio { .. } => {
+                                MEDIA_PART_TOKENS
+                            }
+                        })
+                        .sum(),
+                    None => 0,
+                };
+                let tool_calls: usize = message
+                    .tool_calls
+                    .iter()
+                    .flatten()
+                    .map(|call| {
+                        self.count(&call.function.name) + self.count(&call.function.arguments)
+                    })
+                    .sum();
+
+                MESSAGE_OVERHEAD + content + tool_calls
+            })
+            .sum()
+    }
+}
+
+/// Counter charging one token per text, so each message costs its framing
+/// overhead plus one per text.
+#[cfg(test)]
+pub(crate) struct OneTokenCounter;
+
+#[cfg(test)]
+impl TokenCounter for OneTokenCounter {
+    fn count(&self, _text: &str) -> usize {
+        1
+    }
+}
+
+/// Token counter using a characters-per-token heuristic.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicTokenCounter;
+
+impl TokenCounter for HeuristicTokenCounter {
+    fn count(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(CHARS_PER_TOKEN)
+    }
+}
+
+/// Estimate the token count of a piece of text.
+///
+/// Uses a characters-per-token heuristic, so the result is approximate and
+/// may differ from a model's actual tokenizer.
+pub fn estimate_tokens(text: &str) -> usize {
+    HeuristicTokenCounter.count(text)
+}
+
+/// Estimate the token count of a list of messages.
+pub fn estimate_message_tokens(messages: &[Message]) -> usize {
+    HeuristicTokenCounter.count_messages(messages)
+}
+
+/// Drop the oldest messages until the history fits a token budget.
+///
+/// An assistant message with tool calls and the tool results that follow it
+/// are dropped together, so a call is never separated from its results. With
+/// `keep_system`, system messages are always kept. If the budget cannot be met
+/// by dropping everything droppable, only the kept messages remain.
+///
+/// Returns the number of messages dropped. Tokens are estimated with
+/// [`HeuristicTokenCounter`]; see [`trim_to_budget_with`] for other counters.
+pub fn trim_to_budget(messages: &mut Vec<Message>, budget: usize, keep_system: bool) -> usize {
+    trim_to_budget_with(&HeuristicTokenCounter, messages, budget, keep_system)
+}
+
+/// Drop the oldest messages until the history fits a token budget, counting
+/// tokens with `counter` (see [`trim_to_budget`]).
+pub fn trim_to_budget_with(
+    counter: &dyn TokenCounter,
+    messages: &mut Vec<Message>,
+    budget: usize,
+    keep_system: bool,
+) -> usize {
+    let mut total = counter.count_messages(messages);
+    let mut drop = vec![false; messages.len()];
+
+    let mut start = 0;
+    while start < messages.len() && total > budget {
+        let mut end = start + 1;
+        while end < messages.len() && messages[end].role == Role::Tool {
+            end += 1;
+        }
+
+        if !(keep_system && messages[start].role == Role::System) {
+            drop[start..end].fill(true);
+            total = total.saturating_sub(counter.count_messages(&messages[start..end]));
+        }
+        start = end;
+    }
+
+    let before = messages.len();
+    let mut flags = drop.into_iter();
+    messages.retain(|_| !flags.next().unwrap_or(false));
+    before - messages.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ToolCall;
+
+    #[test]
+    fn test_trim_keeps_system_and_tool_pairs() {
+        let mut messages = vec![
+            Message::system("You are helpful."),
+            Message::user("What's the weather?"),
+            Message::assistant_with_tool_calls(vec![ToolCall::new(
+                "call_1",
+                "get_weather",
+                r#"{"city":"Paris"}"#,
+            )]),
+            Message::tool("call_1", "Sunny"),
+            Message::user("Thanks!"),
+        ];
+        let last = estimate_message_tokens(&messages[4..]);
+        let system = estimate_message_tokens(&messages[..1]);
+
+        let dropped = trim_to_budget(&mut messages, system + last, true);
+
+        assert_eq!(dropped, 3);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, Role::System);
+        assert_eq!(
+            messages[1].content.as_ref().and_then(|c| c.as_text()),
+            Some("Thanks!")
+        );
+    }
+
+    #[test]
+    fn test_trim_with_injected_counter() {
+        let long = "word ".repeat(1000);
+        let mut messages = vec![
+            Message::user(long.as_str()),
+            Message::assistant(long.as_str()),
+            Message::user(long.as_str()),
+        ];
+        let per_message = MESSAGE_OVERHEAD + 1;
+        assert_eq!(OneTokenCounter.count_messages(&messages), 3 * per_message);
+
+        // The heuristic would count thousands of tokens and drop everything.
+        let dropped = trim_to_budget_with(&OneTokenCounter, &mut messages, 2 * per_message, false);
+
+        assert_eq!(dropped, 1);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, Role::Assistant);
+    }
+}
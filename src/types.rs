@@ -1,5 +1,10 @@
 //! Data types for the OpenRouter API.
 
+use crate::conversation::Conversation;
+use crate::error::{OpenRouterError, Result};
+use crate::log;
+use crate::stream::ToolCallAccumulator;
+use crate::tokens::TokenCounter;
 use serde::{Deserialize, Serialize};
 
 /// Message role.
@@ -19,13 +24,25 @@ pub struct Message {
     pub role: Role,
     /// Message content.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub content: Option<String>,
+    pub content: Option<MessageContent>,
+    /// Participant name, distinguishing speakers that share a role.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     /// Tool calls made by the assistant.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<ToolCall>>,
     /// Tool call ID (for tool role messages).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_call_id: Option<String>,
+    /// Refusal message when the model declines to answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refusal: Option<String>,
+    /// Audio output from audio-capable models.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioOutput>,
+    /// Annotations on the content (e.g., web search citations).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Vec<Annotation>>,
 }
 
 impl Message {
@@ -33,9 +50,13 @@ impl Message {
     pub fn system(content: impl Into<String>) -> Self {
         Self {
             role: Role::System,
-            content: Some(content.into()),
+            content: Some(MessageContent::Text(content.into())),
+            name: None,
             tool_calls: None,
             tool_call_id: None,
+            refusal: None,
+            audio: None,
+            annotations: None,
         }
     }
 
@@ -43,9 +64,13 @@ impl Message {
     pub fn user(content: impl Into<String>) -> Self {
         Self {
             role: Role::User,
-            content: Some(content.into()),
+            content: Some(MessageContent::Text(content.into())),
+            name: None,
             tool_calls: None,
             tool_call_id: None,
+            refusal: None,
+            audio: None,
+            annotations: None,
         }
     }
 
@@ -53,19 +78,132 @@ impl Message {
     pub fn assistant(content: impl Into<String>) -> Self {
         Self {
             role: Role::Assistant,
-            content: Some(content.into()),
+            content: Some(MessageContent::Text(content.into())),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            refusal: None,
+            audio: None,
+            annotations: None,
+        }
+    }
+
+    /// Create a user message attributed to a named participant.
+    pub fn user_named(name: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::user(content)
+        }
+    }
+
+    /// Create an assistant message attributed to a named participant, e.g.
+    /// for named few-shot example turns.
+    pub fn assistant_named(name: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::assistant(content)
+        }
+    }
+
+    /// Create a user message from multimodal content parts.
+    pub fn user_with_parts(parts: Vec<ContentPart>) -> Self {
+        Self {
+            role: Role::User,
+            content: Some(MessageContent::Parts(parts)),
+            name: None,
             tool_calls: None,
             tool_call_id: None,
+            refusal: None,
+            audio: None,
+            annotations: None,
         }
     }
 
+    /// Create a user message with text and base64-encoded input audio.
+    ///
+    /// `format` is the audio encoding (e.g., "wav", "mp3").
+    pub fn user_with_audio(
+        text: impl Into<String>,
+        bytes: &[u8],
+        format: impl Into<String>,
+    ) -> Self {
+        Self::user_with_parts(vec![
+            ContentPart::text(text),
+            ContentPart::input_audio(bytes, format),
+        ])
+    }
+
+    /// Check if the message contains image parts.
+    pub fn has_images(&self) -> bool {
+        self.parts()
+            .any(|p| matches!(p, ContentPart::ImageUrl { .. }))
+    }
+
+    /// Check if the message contains audio parts.
+    pub fn has_audio(&self) -> bool {
+        self.parts()
+            .any(|p| matches!(p, ContentPart::InputAudio { .. }))
+    }
+
+    /// Replace image parts with a text placeholder, returning how many were replaced.
+    pub fn replace_images(&mut self, placeholder: &str) -> usize {
+        let Some(MessageContent::Parts(parts)) = &mut self.content else {
+            return 0;
+        };
+        let mut replaced = 0;
+        for part in parts.iter_mut() {
+            if matches!(part, ContentPart::ImageUrl { .. }) {
+                *part = ContentPart::text(placeholder);
+                replaced += 1;
+            }
+        }
+        replaced
+    }
+
+    /// Iterate over the multimodal content parts (empty for text content).
+    fn parts(&self) -> impl Iterator<Item = &ContentPart> {
+        match &self.content {
+            Some(MessageContent::Parts(parts)) => parts.iter(),
+            _ => [].iter(),
+        }
+    }
+
+    /// Create a user message with text and a local image file.
+    ///
+    /// The image is read and embedded as a base64 `data:` URL; the MIME type
+    /// is inferred from the file extension.
+    pub async fn user_with_image_file(
+        text: impl Into<String>,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let mime = image_mime_type(path).ok_or_else(|| {
+            OpenRouterError::InvalidRequest(format!(
+                "Unsupported image file type: {}",
+                path.display()
+            ))
+        })?;
+        let bytes = tokio::fs::read(path).await.map_err(|e| {
+            OpenRouterError::InvalidRequest(format!("Failed to read image {}: {e}", path.display()))
+        })?;
+
+        Ok(Self::user_with_parts(vec![
+            ContentPart::text(text),
+            ContentPart::image_bytes(&bytes, mime)?,
+        ]))
+    }
+
     /// Create an assistant message with tool calls.
     pub fn assistant_with_tool_calls(tool_calls: Vec<ToolCall>) -> Self {
         Self {
             role: Role::Assistant,
             content: None,
+            name: None,
             tool_calls: Some(tool_calls),
             tool_call_id: None,
+            refusal: None,
+            audio: None,
+            annotations: None,
         }
     }
 
@@ -73,11 +211,283 @@ impl Message {
     pub fn tool(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
         Self {
             role: Role::Tool,
-            content: Some(content.into()),
+            content: Some(MessageContent::Text(content.into())),
+            name: None,
             tool_calls: None,
             tool_call_id: Some(tool_call_id.into()),
+            refusal: None,
+            audio: None,
+            annotations: None,
+        }
+    }
+}
+
+/// Message content: plain text or a list of multimodal parts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    /// Plain text content.
+    Text(String),
+    /// Multimodal content parts.
+    Parts(Vec<ContentPart>),
+}
+
+impl MessageContent {
+    /// Create single-part content from raw image bytes.
+    ///
+    /// See [`ContentPart::image_bytes`].
+    pub fn image_bytes(bytes: &[u8], mime: &str) -> Result<Self> {
+        Ok(Self::Parts(vec![ContentPart::image_bytes(bytes, mime)?]))
+    }
+
+    /// Get the content as plain text, if it is text.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text),
+            Self::Parts(_) => None,
+        }
+    }
+
+    /// Get the text of the content, concatenating text parts.
+    ///
+    /// Returns `None` for multimodal content without any text part.
+    pub fn text(&self) -> Option<std::borrow::Cow<'_, str>> {
+        match self {
+            Self::Text(text) => Some(text.as_str().into()),
+            Self::Parts(parts) => {
+                let mut texts = parts.iter().filter_map(|part| match part {
+                    ContentPart::Text { text } => Some(text.as_str()),
+                    _ => None,
+                });
+                let first = texts.next()?;
+                Some(
+                    texts.fold(first.into(), |mut acc: std::borrow::Cow<'_, str>, text| {
+                        acc.to_mut().push_str(text);
+                        acc
+                    }),
+                )
+            }
+        }
+    }
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for MessageContent {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+/// A multimodal content part.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    /// Text part.
+    Text { text: String },
+    /// Image part (URL or base64 `data:` URL).
+    ImageUrl { image_url: ImageUrl },
+    /// Base64-encoded input audio.
+    InputAudio { input_audio: InputAudio },
+}
+
+impl ContentPart {
+    /// Create a text part.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    /// Create an image part from a URL.
+    pub fn image_url(url: impl Into<String>) -> Self {
+        Self::ImageUrl {
+            image_url: ImageUrl {
+                url: url.into(),
+                detail: None,
+            },
+        }
+    }
+
+    /// Create an input audio part from raw bytes.
+    pub fn input_audio(bytes: &[u8], format: impl Into<String>) -> Self {
+        use base64::Engine;
+        Self::InputAudio {
+            input_audio: InputAudio {
+                data: base64::engine::general_purpose::STANDARD.encode(bytes),
+                format: format.into(),
+            },
         }
     }
+
+    /// Create an image part from raw bytes, encoded as a base64 `data:` URL.
+    ///
+    /// `mime` must be an `image/*` MIME type.
+    pub fn image_bytes(bytes: &[u8], mime: &str) -> Result<Self> {
+        if !mime.starts_with("image/") {
+            return Err(OpenRouterError::InvalidRequest(format!(
+                "Expected an image MIME type, got: {mime}"
+            )));
+        }
+
+        use base64::Engine;
+        let data = base64::engine::general_purpose::STANDARD.encode(bytes);
+        Ok(Self::image_url(format!("data:{mime};base64,{data}")))
+    }
+}
+
+/// Image reference.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageUrl {
+    /// Image URL or base64 `data:` URL.
+    pub url: String,
+    /// Detail level ("auto", "low", "high").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Input audio content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputAudio {
+    /// Base64-encoded audio data.
+    pub data: String,
+    /// Audio format (e.g., "wav", "mp3").
+    pub format: String,
+}
+
+/// Audio output returned by a model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioOutput {
+    /// Audio ID.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Base64-encoded audio data.
+    #[serde(default)]
+    pub data: Option<String>,
+    /// Expiration timestamp of the audio.
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    /// Transcript of the audio.
+    #[serde(default)]
+    pub transcript: Option<String>,
+}
+
+/// Annotation attached to message content.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Annotation type (e.g., "url_citation").
+    #[serde(rename = "type")]
+    pub annotation_type: String,
+    /// Citation details for "url_citation" annotations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_citation: Option<UrlCitation>,
+    /// Fields of other annotation types (e.g., `file`), kept as sent.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A cited web source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UrlCitation {
+    /// Source URL.
+    pub url: String,
+    /// Source title.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Excerpt of the source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Start of the cited span in the message content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_index: Option<usize>,
+    /// End of the cited span in the message content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_index: Option<usize>,
+}
+
+/// Infer an image MIME type from a file extension.
+fn image_mime_type(path: &std::path::Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Validate tool-message ordering invariants of a conversation.
+///
+/// Checks that every tool message carries a `tool_call_id` answering a call
+/// from the preceding assistant turn, and that every tool call is answered
+/// before the conversation moves on. User and assistant turns may come in any
+/// order: histories opening with an assistant greeting, or trimmed to fit a
+/// budget, are valid.
+pub fn validate_conversation(messages: &[Message]) -> Result<()> {
+    if messages.is_empty() {
+        return Err(OpenRouterError::InvalidRequest(
+            "Conversation must contain at least one message".to_string(),
+        ));
+    }
+
+    let mut order = ToolCallOrder::default();
+    for (i, message) in messages.iter().enumerate() {
+        order.push(i, message)?;
+    }
+
+    if let Some(id) = order.pending().first() {
+        return Err(OpenRouterError::InvalidRequest(format!(
+            "Tool call {id} has no result"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Incremental check of the tool-message ordering rules of
+/// [`validate_conversation`], shared with [`Conversation`](crate::Conversation).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ToolCallOrder {
+    pending: Vec<String>,
+}
+
+impl ToolCallOrder {
+    /// Check that `message`, at position `index`, may follow the messages seen
+    /// so far, and record it if so.
+    pub(crate) fn push(&mut self, index: usize, message: &Message) -> Result<()> {
+        if message.role == Role::Tool {
+            let id = message.tool_call_id.as_deref().ok_or_else(|| {
+                OpenRouterError::InvalidRequest(format!("Tool message {index} has no tool_call_id"))
+            })?;
+            let pos = self.pending.iter().position(|p| p == id).ok_or_else(|| {
+                OpenRouterError::InvalidRequest(format!(
+                    "Tool message {index} answers unknown tool call: {id}"
+                ))
+            })?;
+            self.pending.remove(pos);
+            return Ok(());
+        }
+
+        if let Some(id) = self.pending.first() {
+            return Err(OpenRouterError::InvalidRequest(format!(
+                "Tool call {id} has no result before message {index}"
+            )));
+        }
+
+        if message.role == Role::Assistant {
+            self.pending
+                .extend(message.tool_calls.iter().flatten().map(|c| c.id.clone()));
+        }
+        Ok(())
+    }
+
+    /// Tool call IDs that still need a result.
+    pub(crate) fn pending(&self) -> &[String] {
+        &self.pending
+    }
 }
 
 /// Tool call made by the assistant.
@@ -116,9 +526,23 @@ pub struct FunctionCall {
     /// Function name.
     pub name: String,
     /// JSON-encoded arguments.
+    ///
+    /// Some providers send an already-parsed JSON object; it is re-encoded as a string.
+    #[serde(deserialize_with = "deserialize_arguments")]
     pub arguments: String,
 }
 
+/// Deserialize function arguments sent either as a JSON string or as a JSON value.
+fn deserialize_arguments<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(arguments) => Ok(arguments),
+        value => Ok(value.to_string()),
+    }
+}
+
 /// Tool definition.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tool {
@@ -145,6 +569,147 @@ impl Tool {
             },
         }
     }
+
+    /// Validate tool call arguments against the declared parameters schema.
+    ///
+    /// Fails with [`OpenRouterError::InvalidResponse`] listing every mismatch
+    /// (e.g., a missing or wrong-typed argument) when the model's arguments do
+    /// not conform, and with [`OpenRouterError::InvalidRequest`] when the
+    /// schema itself is invalid.
+    #[cfg(feature = "schema-validation")]
+    pub fn validate_arguments(&self, args: &str) -> Result<()> {
+        let name = &self.function.name;
+        let validator = jsonschema::validator_for(&self.function.parameters).map_err(|e| {
+            OpenRouterError::InvalidRequest(format!("Invalid parameters schema for {name}: {e}"))
+        })?;
+        let args: serde_json::Value = serde_json::from_str(args).map_err(|e| {
+            OpenRouterError::InvalidResponse(format!("Arguments for {name} are not JSON: {e}"))
+        })?;
+
+        let errors: Vec<String> = validator
+            .iter_errors(&args)
+            .map(|e| match e.instance_path.to_string() {
+                path if path.is_empty() => e.to_string(),
+                path => format!("{path}: {e}"),
+            })
+            .collect();
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(OpenRouterError::InvalidResponse(format!(
+            "Arguments for {name} do not match schema: {}",
+            errors.join("; ")
+        )))
+    }
+}
+
+/// Output format constraint for a chat completion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// Free-form text (the default).
+    Text,
+    /// Any valid JSON object.
+    JsonObject,
+    /// JSON conforming to a schema (structured outputs).
+    JsonSchema { json_schema: JsonSchemaFormat },
+}
+
+impl ResponseFormat {
+    /// Create a JSON schema response format.
+    pub fn json_schema(name: impl Into<String>, schema: serde_json::Value, strict: bool) -> Self {
+        Self::JsonSchema {
+            json_schema: JsonSchemaFormat {
+                name: name.into(),
+                schema,
+                strict: Some(strict),
+            },
+        }
+    }
+}
+
+/// Named JSON schema for [`ResponseFormat::JsonSchema`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSchemaFormat {
+    /// Schema name.
+    pub name: String,
+    /// JSON schema the output must conform to.
+    pub schema: serde_json::Value,
+    /// Whether the schema must be followed exactly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+}
+
+/// Builder for the common "object with typed properties" tool parameter schema.
+#[derive(Debug, Clone, Default)]
+pub struct ParametersSchema {
+    properties: serde_json::Map<String, serde_json::Value>,
+    required: Vec<String>,
+}
+
+impl ParametersSchema {
+    /// Start an object schema with no properties.
+    pub fn object() -> Self {
+        Self::default()
+    }
+
+    /// Add a string property.
+    pub fn string(self, name: &str, description: &str, required: bool) -> Self {
+        self.property(name, "string", description, required)
+    }
+
+    /// Add a number property.
+    pub fn number(self, name: &str, description: &str, required: bool) -> Self {
+        self.property(name, "number", description, required)
+    }
+
+    /// Add an integer property.
+    pub fn integer(self, name: &str, description: &str, required: bool) -> Self {
+        self.property(name, "integer", description, required)
+    }
+
+    /// Add a boolean property.
+    pub fn boolean(self, name: &str, description: &str, required: bool) -> Self {
+        self.property(name, "boolean", description, required)
+    }
+
+    /// Add a string property restricted to the given values.
+    pub fn string_enum(
+        mut self,
+        name: &str,
+        description: &str,
+        values: &[&str],
+        required: bool,
+    ) -> Self {
+        self = self.property(name, "string", description, required);
+        if let Some(property) = self.properties.get_mut(name) {
+            property["enum"] = serde_json::json!(values);
+        }
+        self
+    }
+
+    /// Add a property with an arbitrary schema.
+    pub fn custom(mut self, name: &str, schema: serde_json::Value, required: bool) -> Self {
+        self.properties.insert(name.to_string(), schema);
+        if required {
+            self.required.push(name.to_string());
+        }
+        self
+    }
+
+    fn property(self, name: &str, kind: &str, description: &str, required: bool) -> Self {
+        let schema = serde_json::json!({ "type": kind, "description": description });
+        self.custom(name, schema, required)
+    }
+
+    /// Build the JSON schema value expected by [`Tool::function`].
+    pub fn build(self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": self.properties,
+            "required": self.required,
+        })
+    }
 }
 
 /// Function definition.
@@ -158,6 +723,35 @@ pub struct FunctionDefinition {
     pub parameters: serde_json::Value,
 }
 
+/// Legacy `function_call` selection for [`CreateChatCompletionRequest::functions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FunctionCallChoice {
+    /// Never call a function (`"none"`).
+    None,
+    /// Let the model decide (`"auto"`).
+    Auto,
+    /// Force a call to the named function (`{"name": ...}`).
+    Function(String),
+}
+
+impl Serialize for FunctionCallChoice {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Named<'a> {
+            name: &'a str,
+        }
+
+        match self {
+            Self::None => serializer.serialize_str("none"),
+            Self::Auto => serializer.serialize_str("auto"),
+            Self::Function(name) => Named { name }.serialize(serializer),
+        }
+    }
+}
+
 /// Provider preferences for routing.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProviderPreferences {
@@ -171,18 +765,54 @@ pub struct ProviderPreferences {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data_collection: Option<String>,
     /// Provider order preference.
+    ///
+    /// A soft preference: other providers may still be used. See `only` for a
+    /// hard restriction.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub order: Option<Vec<String>>,
+    /// Exclusive provider allowlist.
+    ///
+    /// A hard constraint: requests are only routed to these providers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub only: Option<Vec<String>>,
     /// Providers to ignore.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ignore: Option<Vec<String>>,
+    /// Restrict routing to zero data retention endpoints.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zdr: Option<bool>,
     /// Quantization preference (e.g., "int4", "int8", "fp6", "fp8", "fp16", "bf16").
     #[serde(skip_serializing_if = "Option::is_none")]
     pub quantizations: Option<Vec<String>>,
 }
 
+impl ProviderPreferences {
+    /// Preset for maximum data protection.
+    ///
+    /// Sets `data_collection` to `"deny"` (skip providers that may store or
+    /// train on prompts) and `zdr` to `true` (zero data retention endpoints
+    /// only). All other fields are left unset.
+    pub fn privacy_strict() -> Self {
+        Self::default().with_privacy_strict()
+    }
+
+    /// Apply the [`privacy_strict`](Self::privacy_strict) settings, keeping other fields.
+    pub fn with_privacy_strict(mut self) -> Self {
+        self.data_collection = Some("deny".to_string());
+        self.zdr = Some(true);
+        self
+    }
+
+    /// Restrict routing to the given providers.
+    pub fn with_only(mut self, providers: Vec<String>) -> Self {
+        self.only = Some(providers);
+        self
+    }
+}
+
 /// Request to create a chat completion.
 #[derive(Debug, Clone, Serialize)]
+#[serde(remote = "Self")]
 pub struct CreateChatCompletionRequest {
     /// Model to use (e.g., "openai/gpt-4o", "anthropic/claude-3.5-sonnet").
     pub model: String,
@@ -203,6 +833,18 @@ pub struct CreateChatCompletionRequest {
     /// Available tools.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tool>>,
+    /// Legacy function definitions, superseded by `tools`.
+    ///
+    /// Only for middleware and providers that predate the tools schema;
+    /// prefer [`tools`](Self::tools).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub functions: Option<Vec<FunctionDefinition>>,
+    /// Legacy function selection for `functions`, superseded by `tool_choice`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function_call: Option<FunctionCallChoice>,
+    /// Output format constraint (JSON mode or JSON schema).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
     /// Whether to stream the response.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
@@ -215,6 +857,15 @@ pub struct CreateChatCompletionRequest {
     /// Frequency penalty.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frequency_penalty: Option<f32>,
+    /// Seed for deterministic sampling (best effort, provider-dependent).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    /// Whether to return log probabilities of the output tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<bool>,
+    /// Number of most likely alternatives to return per token (requires `logprobs`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<usize>,
     /// Provider routing preferences (OpenRouter-specific).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub provider: Option<ProviderPreferences>,
@@ -224,6 +875,56 @@ pub struct CreateChatCompletionRequest {
     /// Route to select model based on prompt (OpenRouter-specific).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub route: Option<String>,
+    /// Stable end-user identifier, used for abuse detection and activity reports.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Key/value tags attached to the request (e.g., `tenant_id`, `feature`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Map<String, serde_json::Value>>,
+    /// Additional provider-specific parameters (e.g., `top_a`, `tfs`, `typical_p`).
+    ///
+    /// Merged into the request body. A key whose typed field is set is
+    /// dropped so the typed field always wins.
+    #[serde(skip)]
+    pub extra: Option<serde_json::Map<String, serde_json::Value>>,
+    /// HTTP headers sent with this request only (e.g., `anthropic-beta` opt-ins).
+    ///
+    /// Not part of the JSON body.
+    #[serde(skip)]
+    pub extra_headers: reqwest::header::HeaderMap,
+    /// Bypass [`ClientBuilder::max_cost_per_request`](crate::ClientBuilder::max_cost_per_request)
+    /// for this request.
+    #[serde(skip)]
+    pub skip_cost_cap: bool,
+    /// Re-sends allowed when a typed completion returns invalid JSON (see
+    /// [`with_json_retry`](Self::with_json_retry)).
+    #[serde(skip)]
+    pub json_retries: u32,
+}
+
+const TEMPERATURE_RANGE: std::ops::RangeInclusive<f32> = 0.0..=2.0;
+const TOP_P_RANGE: std::ops::RangeInclusive<f32> = 0.0..=1.0;
+const PENALTY_RANGE: std::ops::RangeInclusive<f32> = -2.0..=2.0;
+
+// Serialized through the derived `Self::serialize`, generated as an inherent
+// function by `remote = "Self"`, then merged with `extra`.
+impl Serialize for CreateChatCompletionRequest {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+
+        let mut value = CreateChatCompletionRequest::serialize(self, serde_json::value::Serializer)
+            .map_err(S::Error::custom)?;
+        if let (serde_json::Value::Object(body), Some(extra)) = (&mut value, &self.extra) {
+            for (key, extra_value) in extra {
+                body.entry(key.as_str())
+                    .or_insert_with(|| extra_value.clone());
+            }
+        }
+        value.serialize(serializer)
+    }
 }
 
 impl CreateChatCompletionRequest {
@@ -237,14 +938,126 @@ impl CreateChatCompletionRequest {
             top_p: None,
             stop: None,
             tools: None,
+            functions: None,
+            function_call: None,
+            response_format: None,
             stream: None,
             n: None,
             presence_penalty: None,
             frequency_penalty: None,
+            seed: None,
+            logprobs: None,
+            top_logprobs: None,
             provider: None,
             models: None,
             route: None,
+            user: None,
+            metadata: None,
+            extra: None,
+            extra_headers: reqwest::header::HeaderMap::new(),
+            skip_cost_cap: false,
+            json_retries: 0,
+        }
+    }
+
+    /// Create a chat completion request from a managed conversation.
+    pub fn from_conversation(model: impl Into<String>, conversation: &Conversation) -> Self {
+        Self::new(model, conversation.messages().to_vec())
+    }
+
+    /// Validate the request before sending.
+    ///
+    /// Checks tool-message ordering and that sampling parameters are in range:
+    /// `temperature` in `0..=2`, `top_p` in `0..=1`, and `presence_penalty` /
+    /// `frequency_penalty` in `-2..=2`.
+    pub fn validate(&self) -> Result<()> {
+        validate_conversation(&self.messages)?;
+
+        for (name, value, range) in self.sampling_params() {
+            if let Some(value) = value {
+                if !range.contains(&value) {
+                    return Err(OpenRouterError::InvalidRequest(format!(
+                        "{name} must be in {}..={}, got {value}",
+                        range.start(),
+                        range.end()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Clamp sampling parameters into their valid ranges (see [`validate`](Self::validate)).
+    pub fn clamp_sampling_params(&mut self) {
+        let clamp = |value: &mut Option<f32>, range: std::ops::RangeInclusive<f32>| {
+            if let Some(v) = value {
+                *v = v.clamp(*range.start(), *range.end());
+            }
+        };
+        clamp(&mut self.temperature, TEMPERATURE_RANGE);
+        clamp(&mut self.top_p, TOP_P_RANGE);
+        clamp(&mut self.presence_penalty, PENALTY_RANGE);
+        clamp(&mut self.frequency_penalty, PENALTY_RANGE);
+    }
+
+    fn sampling_params(&self) -> [(&'static str, Option<f32>, std::ops::RangeInclusive<f32>); 4] {
+        [
+            ("temperature", self.temperature, TEMPERATURE_RANGE),
+            ("top_p", self.top_p, TOP_P_RANGE),
+            ("presence_penalty", self.presence_penalty, PENALTY_RANGE),
+            ("frequency_penalty", self.frequency_penalty, PENALTY_RANGE),
+        ]
+    }
+
+    /// Validate the request against a model's advertised capabilities.
+    ///
+    /// Rejects image or audio content sent to a model whose input modalities
+    /// do not include them, and tools or a response format the model does not
+    /// list in its supported parameters (which OpenRouter would silently ignore).
+    pub fn validate_for_model(&self, model: &Model) -> Result<()> {
+        let features = [
+            (
+                "tools",
+                self.tools.as_ref().is_some_and(|t| !t.is_empty())
+                    || self.functions.as_ref().is_some_and(|f| !f.is_empty()),
+                model.supports_tools(),
+            ),
+            (
+                "JSON mode",
+                matches!(self.response_format, Some(ResponseFormat::JsonObject)),
+                model.supports_response_format(),
+            ),
+            (
+                "structured outputs",
+                matches!(
+                    self.response_format,
+                    Some(ResponseFormat::JsonSchema { .. })
+                ),
+                model.supports_structured_outputs(),
+            ),
+        ];
+        for (feature, used, supported) in features {
+            if used && !supported {
+                return Err(OpenRouterError::InvalidRequest(format!(
+                    "Model {} does not support {feature}",
+                    model.id
+                )));
+            }
+        }
+
+        let checks = [
+            ("image", self.messages.iter().any(Message::has_images)),
+            ("audio", self.messages.iter().any(Message::has_audio)),
+        ];
+        for (modality, used) in checks {
+            if used && !model.supports_input_modality(modality) {
+                return Err(OpenRouterError::InvalidRequest(format!(
+                    "Model {} does not support {modality} input",
+                    model.id
+                )));
+            }
         }
+        Ok(())
     }
 
     /// Set max tokens.
@@ -277,12 +1090,31 @@ impl CreateChatCompletionRequest {
         self
     }
 
+    /// Set legacy function definitions (prefer [`with_tools`](Self::with_tools)).
+    pub fn with_functions(mut self, functions: Vec<FunctionDefinition>) -> Self {
+        self.functions = Some(functions);
+        self
+    }
+
+    /// Set the legacy function selection for [`with_functions`](Self::with_functions).
+    pub fn with_function_call(mut self, choice: FunctionCallChoice) -> Self {
+        self.function_call = Some(choice);
+        self
+    }
+
     /// Set provider preferences.
     pub fn with_provider(mut self, provider: ProviderPreferences) -> Self {
         self.provider = Some(provider);
         self
     }
 
+    /// Apply the [`ProviderPreferences::privacy_strict`] settings to the
+    /// provider preferences, keeping any other preferences already set.
+    pub fn with_zdr(mut self) -> Self {
+        self.provider = Some(self.provider.unwrap_or_default().with_privacy_strict());
+        self
+    }
+
     /// Set fallback models.
     pub fn with_fallback_models(mut self, models: Vec<String>) -> Self {
         self.models = Some(models);
@@ -294,37 +1126,286 @@ impl CreateChatCompletionRequest {
         self.route = Some(route.into());
         self
     }
+
+    /// Set the response format.
+    pub fn with_response_format(mut self, format: ResponseFormat) -> Self {
+        self.response_format = Some(format);
+        self
+    }
+
+    /// Set the sampling seed.
+    pub fn with_seed(mut self, seed: i64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Request log probabilities of the output tokens, with up to `top`
+    /// alternatives per token.
+    pub fn with_logprobs(mut self, top: usize) -> Self {
+        self.logprobs = Some(true);
+        self.top_logprobs = (top > 0).then_some(top);
+        self
+    }
+
+    /// Set the end-user identifier.
+    pub fn with_user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Attach key/value metadata for request tagging.
+    pub fn with_metadata(mut self, metadata: serde_json::Map<String, serde_json::Value>) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Add an HTTP header sent with this request only.
+    ///
+    /// Headers are not part of the JSON body; use this for per-request opt-ins
+    /// such as `anthropic-beta: prompt-caching-2024-07-31`.
+    pub fn with_header(mut self, name: &str, value: &str) -> Result<Self> {
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| OpenRouterError::InvalidRequest(format!("Invalid header name: {name}")))?;
+        let value = reqwest::header::HeaderValue::from_str(value).map_err(|_| {
+            OpenRouterError::InvalidRequest(format!("Invalid value for header {name}"))
+        })?;
+        self.extra_headers.insert(name, value);
+        Ok(self)
+    }
+
+    /// Send this request even if its estimated cost exceeds the client's
+    /// per-request cap.
+    pub fn without_cost_cap(mut self) -> Self {
+        self.skip_cost_cap = true;
+        self
+    }
+
+    /// Re-send up to `retries` times when a typed completion's answer is not
+    /// valid JSON for the schema (disabled by default).
+    ///
+    /// Each retry appends the invalid answer and a reminder to reply with
+    /// valid JSON, and is a separate, billed generation. Only used by
+    /// `Client::create_chat_completion_typed`.
+    pub fn with_json_retry(mut self, retries: u32) -> Self {
+        self.json_retries = retries;
+        self
+    }
+
+    /// Add a provider-specific parameter not covered by the typed fields.
+    pub fn with_extra(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        self.extra
+            .get_or_insert_with(serde_json::Map::new)
+            .insert(key.into(), value.into());
+        self
+    }
 }
 
 /// Token usage statistics.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Usage {
     /// Prompt tokens.
+    #[serde(deserialize_with = "deserialize_token_count")]
     pub prompt_tokens: usize,
     /// Completion tokens.
+    #[serde(deserialize_with = "deserialize_token_count")]
     pub completion_tokens: usize,
     /// Total tokens.
+    #[serde(deserialize_with = "deserialize_token_count")]
     pub total_tokens: usize,
 }
 
-/// A completion choice.
-#[derive(Debug, Clone, Deserialize)]
-pub struct Choice {
-    /// Choice index.
-    pub index: usize,
-    /// Generated message.
-    pub message: Message,
-    /// Finish reason.
-    pub finish_reason: Option<String>,
+/// Running token and cost totals across many responses.
+///
+/// Responses without `usage` are counted but add no tokens or cost.
+#[derive(Debug, Clone, Default)]
+pub struct UsageAccumulator {
+    prompt_tokens: usize,
+    completion_tokens: usize,
+    total_tokens: usize,
+    cost: f64,
+    responses: usize,
+    missing_usage: usize,
 }
 
-/// Response from creating a chat completion.
-#[derive(Debug, Clone, Deserialize)]
-pub struct CreateChatCompletionResponse {
-    /// Response ID.
-    pub id: String,
-    /// Object type.
-    pub object: String,
+impl UsageAccumulator {
+    /// Create an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a response's usage, priced with the model that produced it.
+    ///
+    /// Tokens are still counted when the model's pricing cannot be parsed.
+    pub fn push(&mut self, usage: Option<&Usage>, model: &Model) {
+        self.responses += 1;
+        let Some(usage) = usage else {
+            self.missing_usage += 1;
+            return;
+        };
+
+        self.prompt_tokens += usage.prompt_tokens;
+        self.completion_tokens += usage.completion_tokens;
+        self.total_tokens += usage.total_tokens;
+        if let (Some(prompt), Some(completion)) = (
+            model.pricing.prompt_price(),
+            model.pricing.completion_price(),
+        ) {
+            self.cost +=
+                usage.prompt_tokens as f64 * prompt + usage.completion_tokens as f64 * completion;
+        }
+    }
+
+    /// Add a chat completion response (see [`push`](Self::push)).
+    pub fn push_response(&mut self, response: &CreateChatCompletionResponse, model: &Model) {
+        self.push(response.usage.as_ref(), model);
+    }
+
+    /// Total token usage so far.
+    pub fn total(&self) -> Usage {
+        Usage {
+            prompt_tokens: self.prompt_tokens,
+            completion_tokens: self.completion_tokens,
+            total_tokens: self.total_tokens,
+        }
+    }
+
+    /// Estimated cost so far in USD.
+    pub fn cost(&self) -> f64 {
+        self.cost
+    }
+
+    /// Number of responses added.
+    pub fn responses(&self) -> usize {
+        self.responses
+    }
+
+    /// Number of responses added without usage information.
+    pub fn missing_usage(&self) -> usize {
+        self.missing_usage
+    }
+}
+
+/// Deserialize a token count reported as an integer or a float (e.g., `12.0`).
+fn deserialize_token_count<'de, D>(deserializer: D) -> std::result::Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = f64::deserialize(deserializer)?;
+    if !value.is_finite() || value < 0.0 {
+        return Err(serde::de::Error::custom(format!(
+            "invalid token count: {value}"
+        )));
+    }
+    Ok(value.round() as usize)
+}
+
+/// A completion choice.
+///
+/// A choice carrying a streaming-shaped `delta` instead of `message`, as some
+/// misconfigured proxies return to non-streaming requests, is coalesced into
+/// a message with a warning.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawChoice")]
+pub struct Choice {
+    /// Choice index.
+    pub index: usize,
+    /// Generated message.
+    pub message: Message,
+    /// Finish reason.
+    pub finish_reason: Option<String>,
+    /// Log probabilities of the output tokens, if requested.
+    pub logprobs: Option<ChoiceLogprobs>,
+    /// Provider error that aborted this choice mid-generation.
+    pub error: Option<ErrorDetail>,
+}
+
+/// Wire shape of a choice, accepting either `message` or `delta`.
+#[derive(Deserialize)]
+struct RawChoice {
+    index: usize,
+    #[serde(default)]
+    message: Option<Message>,
+    #[serde(default)]
+    delta: Option<ChunkDelta>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    logprobs: Option<ChoiceLogprobs>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    error: Option<ErrorDetail>,
+}
+
+impl TryFrom<RawChoice> for Choice {
+    type Error = String;
+
+    fn try_from(raw: RawChoice) -> std::result::Result<Self, Self::Error> {
+        let message = match (raw.message, raw.delta) {
+            (Some(message), _) => message,
+            (None, Some(delta)) => {
+                log::warn!(
+                    index = raw.index,
+                    "Coalescing delta choice in buffered response"
+                );
+                delta.into_message()
+            }
+            (None, None) => return Err("missing field `message`".to_string()),
+        };
+        Ok(Self {
+            index: raw.index,
+            message,
+            finish_reason: raw.finish_reason,
+            logprobs: raw.logprobs,
+            error: raw.error,
+        })
+    }
+}
+
+/// Log probabilities of a choice's output tokens.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChoiceLogprobs {
+    /// Log probabilities of the content tokens, in order.
+    #[serde(default)]
+    pub content: Option<Vec<TokenLogprob>>,
+}
+
+/// Log probability of one output token.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenLogprob {
+    /// The token.
+    pub token: String,
+    /// Natural log probability of the token.
+    pub logprob: f64,
+    /// UTF-8 bytes of the token, if provided.
+    #[serde(default)]
+    pub bytes: Option<Vec<u8>>,
+    /// Most likely alternatives at this position.
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+/// An alternative token and its log probability.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TopLogprob {
+    /// The token.
+    pub token: String,
+    /// Natural log probability of the token.
+    pub logprob: f64,
+    /// UTF-8 bytes of the token, if provided.
+    #[serde(default)]
+    pub bytes: Option<Vec<u8>>,
+}
+
+/// Response from creating a chat completion.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateChatCompletionResponse {
+    /// Response ID.
+    pub id: String,
+    /// Object type.
+    pub object: String,
     /// Creation timestamp.
     pub created: u64,
     /// Model used.
@@ -332,15 +1413,100 @@ pub struct CreateChatCompletionResponse {
     /// Completion choices.
     pub choices: Vec<Choice>,
     /// Token usage.
+    ///
+    /// Malformed usage is logged and dropped rather than failing the response.
+    #[serde(default, deserialize_with = "deserialize_lenient")]
     pub usage: Option<Usage>,
+    /// Backend configuration fingerprint, for detecting changes that affect determinism.
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub system_fingerprint: Option<String>,
+    /// Seed echoed by the provider, if it reports one.
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub seed: Option<i64>,
+}
+
+/// Deserialize an auxiliary field, treating a malformed value as absent.
+///
+/// Keeps a response usable when a provider sends an unexpected shape for a
+/// field that is not essential to the answer.
+fn deserialize_lenient<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    if value.is_null() {
+        return Ok(None);
+    }
+    match serde_json::from_value(value) {
+        Ok(value) => Ok(Some(value)),
+        Err(_error) => {
+            log::warn!(error = %_error, "Ignoring malformed response field");
+            Ok(None)
+        }
+    }
 }
 
+/// `object` value of a chat completion response.
+pub const CHAT_COMPLETION_OBJECT: &str = "chat.completion";
+
+/// `object` value of a streamed chat completion chunk.
+pub const CHAT_COMPLETION_CHUNK_OBJECT: &str = "chat.completion.chunk";
+
 impl CreateChatCompletionResponse {
+    /// Check that the response is a chat completion.
+    ///
+    /// Catches clients pointed at the wrong endpoint or proxies returning a
+    /// different object type.
+    ///
+    /// Also rejects choices cut off by `max_tokens` whose tool call arguments
+    /// are not valid JSON, with [`OpenRouterError::TruncatedToolCall`], and
+    /// choices aborted by a provider error (`finish_reason: "error"`), with
+    /// [`OpenRouterError::ServerError`].
+    pub fn validate(&self) -> Result<()> {
+        if self.object != CHAT_COMPLETION_OBJECT {
+            return Err(OpenRouterError::InvalidResponse(format!(
+                "Expected object \"{CHAT_COMPLETION_OBJECT}\", got \"{}\"",
+                self.object
+            )));
+        }
+
+        for choice in &self.choices {
+            check_choice_error(choice.finish_reason.as_deref(), choice.error.as_ref())?;
+        }
+
+        let truncated = self
+            .choices
+            .iter()
+            .filter(|c| c.finish_reason.as_deref() == Some("length"))
+            .flat_map(|c| c.message.tool_calls.iter().flatten())
+            .find(|call| {
+                serde_json::from_str::<serde_json::Value>(&call.function.arguments).is_err()
+            });
+        if let Some(call) = truncated {
+            return Err(OpenRouterError::TruncatedToolCall {
+                name: call.function.name.clone(),
+                arguments: call.function.arguments.clone(),
+            });
+        }
+        Ok(())
+    }
+
     /// Get the first choice's message content.
-    pub fn content(&self) -> Option<&str> {
+    ///
+    /// Content returned as an array of parts is flattened into its text.
+    pub fn content(&self) -> Option<std::borrow::Cow<'_, str>> {
+        self.choices
+            .first()
+            .and_then(|c| c.message.content.as_ref())
+            .and_then(MessageContent::text)
+    }
+
+    /// Get the first choice's refusal message, if the model declined.
+    pub fn refusal(&self) -> Option<&str> {
         self.choices
             .first()
-            .and_then(|c| c.message.content.as_deref())
+            .and_then(|c| c.message.refusal.as_deref())
     }
 
     /// Get the first choice's tool calls.
@@ -350,6 +1516,89 @@ impl CreateChatCompletionResponse {
             .and_then(|c| c.message.tool_calls.as_ref())
     }
 
+    /// Get the first choice's tool calls, failing if there are none.
+    pub fn require_tool_calls(&self) -> Result<&[ToolCall]> {
+        match self.tool_calls() {
+            Some(calls) if !calls.is_empty() => Ok(calls),
+            _ => Err(OpenRouterError::InvalidResponse(
+                "Expected tool calls in response".to_string(),
+            )),
+        }
+    }
+
+    /// Get the only tool call of the first choice, failing unless there is exactly one.
+    pub fn single_tool_call(&self) -> Result<&ToolCall> {
+        match self.require_tool_calls()? {
+            [call] => Ok(call),
+            calls => Err(OpenRouterError::InvalidResponse(format!(
+                "Expected a single tool call, got {}",
+                calls.len()
+            ))),
+        }
+    }
+
+    /// Check if the response carries no answer: no choices, or a first choice
+    /// without content, tool calls, or a refusal.
+    pub fn is_empty(&self) -> bool {
+        let Some(choice) = self.choices.first() else {
+            return true;
+        };
+        let message = &choice.message;
+        message
+            .content
+            .as_ref()
+            .and_then(MessageContent::text)
+            .is_none_or(|t| t.trim().is_empty())
+            && message.tool_calls.as_ref().is_none_or(Vec::is_empty)
+            && message.refusal.is_none()
+    }
+
+    /// Get the token log probabilities of the choice at `choice_index`, if
+    /// they were requested and returned.
+    pub fn logprobs(&self, choice_index: usize) -> Option<&[TokenLogprob]> {
+        self.choices
+            .iter()
+            .find(|c| c.index == choice_index)
+            .and_then(|c| c.logprobs.as_ref())
+            .and_then(|l| l.content.as_deref())
+    }
+
+    /// Sum of the first choice's token log probabilities: the log probability
+    /// of the whole output.
+    pub fn total_logprob(&self) -> Option<f64> {
+        let logprobs = self.logprobs(0).filter(|l| !l.is_empty())?;
+        Some(logprobs.iter().map(|t| t.logprob).sum())
+    }
+
+    /// Mean of the first choice's token log probabilities.
+    ///
+    /// A length-independent confidence score; `exp` of it is the geometric
+    /// mean token probability, in `0.0..=1.0`.
+    pub fn mean_logprob(&self) -> Option<f64> {
+        let count = self.logprobs(0)?.len();
+        Some(self.total_logprob()? / count as f64)
+    }
+
+    /// Check that the provider honored the request's `seed`.
+    ///
+    /// Returns `false` and logs a warning when the provider echoed a different
+    /// seed. Passes when the request set no seed or the provider did not echo
+    /// one, since most providers omit it.
+    pub fn verify_seed(&self, request: &CreateChatCompletionRequest) -> bool {
+        let mismatch = matches!(
+            (request.seed, self.seed),
+            (Some(requested), Some(echoed)) if requested != echoed
+        );
+        if mismatch {
+            log::warn!(
+                requested = ?request.seed,
+                echoed = ?self.seed,
+                "Provider did not honor the requested seed"
+            );
+        }
+        !mismatch
+    }
+
     /// Check if the response contains tool calls.
     pub fn has_tool_calls(&self) -> bool {
         self.choices
@@ -358,6 +1607,274 @@ impl CreateChatCompletionResponse {
     }
 }
 
+/// Evidence that OpenRouter's `middle-out` transform compressed a prompt.
+///
+/// The API reports no explicit flag, so truncation is inferred when the
+/// reported prompt tokens fall well below the client-side estimate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptTruncation {
+    /// Estimated prompt tokens of the request as sent.
+    pub estimated_tokens: usize,
+    /// Prompt tokens the API reported after transforms.
+    pub prompt_tokens: usize,
+}
+
+impl PromptTruncation {
+    /// Detect prompt truncation, with prompt tokens estimated by `counter`.
+    ///
+    /// Returns `None` unless the request enabled the `middle-out` transform
+    /// (via `transforms` in [`extra`](CreateChatCompletionRequest::extra)),
+    /// the response reports usage, and fewer than half the estimated tokens
+    /// were billed. The margin absorbs tokenizer differences, so modest trims
+    /// may go undetected.
+    pub fn detect(
+        request: &CreateChatCompletionRequest,
+        response: &CreateChatCompletionResponse,
+        counter: &dyn TokenCounter,
+    ) -> Option<Self> {
+        let middle_out = request
+            .extra
+            .as_ref()
+            .and_then(|extra| extra.get("transforms"))
+            .and_then(|transforms| transforms.as_array())
+            .is_some_and(|transforms| transforms.iter().any(|t| t == "middle-out"));
+        if !middle_out {
+            return None;
+        }
+
+        let prompt_tokens = response.usage.as_ref()?.prompt_tokens;
+        let estimated_tokens = counter.count_messages(&request.messages);
+        (prompt_tokens < estimated_tokens / 2).then_some(Self {
+            estimated_tokens,
+            prompt_tokens,
+        })
+    }
+}
+
+/// Result of comparing two responses to the same request for reproducibility.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReproducibilityCheck {
+    /// Both responses report a `system_fingerprint` and they differ.
+    pub fingerprint_changed: bool,
+    /// The generated content or tool calls differ.
+    pub outputs_diverged: bool,
+}
+
+impl ReproducibilityCheck {
+    /// Compare two responses produced by the same request.
+    ///
+    /// Logs a warning when the backend fingerprint changed, or when the request
+    /// set a `seed` and the outputs still diverged.
+    pub fn compare(
+        request: &CreateChatCompletionRequest,
+        first: &CreateChatCompletionResponse,
+        second: &CreateChatCompletionResponse,
+    ) -> Self {
+        let fingerprint_changed = match (&first.system_fingerprint, &second.system_fingerprint) {
+            (Some(a), Some(b)) => a != b,
+            _ => false,
+        };
+        let outputs_diverged = first.choices.len() != second.choices.len()
+            || first
+                .choices
+                .iter()
+                .zip(&second.choices)
+                .any(|(a, b)| !same_output(&a.message, &b.message));
+
+        if fingerprint_changed {
+            log::warn!(
+                first = ?first.system_fingerprint,
+                second = ?second.system_fingerprint,
+                "System fingerprint changed between responses"
+            );
+        }
+        if outputs_diverged && request.seed.is_some() {
+            log::warn!(seed = ?request.seed, "Seeded responses diverged");
+        }
+
+        Self {
+            fingerprint_changed,
+            outputs_diverged,
+        }
+    }
+
+    /// Whether the backend was unchanged and the outputs matched.
+    pub fn is_reproducible(&self) -> bool {
+        !self.fingerprint_changed && !self.outputs_diverged
+    }
+}
+
+/// Compare generated content and tool calls, ignoring per-response tool call IDs.
+fn same_output(a: &Message, b: &Message) -> bool {
+    let content = |m: &Message| m.content.as_ref().and_then(|c| c.text().map(String::from));
+    let calls = |m: &Message| {
+        m.tool_calls
+            .iter()
+            .flatten()
+            .map(|c| (c.function.name.clone(), c.function.arguments.clone()))
+            .collect::<Vec<_>>()
+    };
+    content(a) == content(b) && calls(a) == calls(b)
+}
+
+/// A streamed chat completion chunk.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatCompletionChunk {
+    /// Response ID (shared by every chunk of a stream).
+    #[serde(default)]
+    pub id: String,
+    /// Object type ("chat.completion.chunk").
+    #[serde(default)]
+    pub object: String,
+    /// Creation timestamp.
+    #[serde(default)]
+    pub created: u64,
+    /// Model used.
+    #[serde(default)]
+    pub model: String,
+    /// Incremental choices.
+    #[serde(default)]
+    pub choices: Vec<ChunkChoice>,
+    /// Token usage (usually only on the final chunk).
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub usage: Option<Usage>,
+}
+
+impl ChatCompletionChunk {
+    /// Check that the chunk is a chat completion chunk.
+    ///
+    /// Chunks without an `object` field (e.g. keep-alive frames) are accepted.
+    pub fn validate(&self) -> Result<()> {
+        if !self.object.is_empty() && self.object != CHAT_COMPLETION_CHUNK_OBJECT {
+            return Err(OpenRouterError::InvalidResponse(format!(
+                "Expected object \"{CHAT_COMPLETION_CHUNK_OBJECT}\", got \"{}\"",
+                self.object
+            )));
+        }
+        for choice in &self.choices {
+            check_choice_error(choice.finish_reason.as_deref(), choice.error.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Check if this is a keep-alive frame, such as `{"choices":[]}`, sent to
+    /// hold the connection open: no choices and no usage.
+    ///
+    /// Chunks with a choice are never keep-alives, even if the delta is empty.
+    pub fn is_keep_alive(&self) -> bool {
+        self.choices.is_empty() && self.usage.is_none()
+    }
+}
+
+/// Fail with [`OpenRouterError::ServerError`] if a choice was aborted by a
+/// provider error, using the embedded error message when there is one.
+fn check_choice_error(finish_reason: Option<&str>, error: Option<&ErrorDetail>) -> Result<()> {
+    match (finish_reason, error) {
+        (_, Some(error)) => Err(OpenRouterError::ServerError(error.message.clone())),
+        (Some("error"), None) => Err(OpenRouterError::ServerError(
+            "Provider aborted the generation".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// An incremental completion choice.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChunkChoice {
+    /// Choice index.
+    #[serde(default)]
+    pub index: usize,
+    /// Message delta.
+    #[serde(default)]
+    pub delta: ChunkDelta,
+    /// Finish reason (set on the last chunk of the choice).
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    /// Provider error that aborted this choice mid-generation.
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub error: Option<ErrorDetail>,
+}
+
+/// Incremental message content.
+///
+/// Reasoning models stream their thinking in `reasoning`, separately from the
+/// final answer in `content`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ChunkDelta {
+    /// Message role (usually only on the first chunk).
+    #[serde(default)]
+    pub role: Option<Role>,
+    /// Answer content delta.
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Reasoning delta.
+    #[serde(default)]
+    pub reasoning: Option<String>,
+    /// Refusal delta.
+    #[serde(default)]
+    pub refusal: Option<String>,
+    /// Annotations added by this chunk.
+    #[serde(default)]
+    pub annotations: Option<Vec<Annotation>>,
+    /// Tool call deltas.
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+impl ChunkDelta {
+    /// Convert a single delta into a complete message, assembling its tool
+    /// call fragments.
+    fn into_message(self) -> Message {
+        let mut tool_calls = ToolCallAccumulator::new();
+        for tool_call in self.tool_calls.iter().flatten() {
+            tool_calls.push(tool_call);
+        }
+        let tool_calls = tool_calls.tool_calls();
+        Message {
+            role: self.role.unwrap_or(Role::Assistant),
+            content: self.content.map(MessageContent::Text),
+            name: None,
+            tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+            tool_call_id: None,
+            refusal: self.refusal,
+            audio: None,
+            annotations: self.annotations,
+        }
+    }
+}
+
+/// Incremental tool call.
+///
+/// Parallel tool calls are distinguished by `index`; `id` and the function
+/// name are only present on the first delta for each index.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ToolCallDelta {
+    /// Position of the tool call in the message.
+    #[serde(default)]
+    pub index: usize,
+    /// Tool call ID.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Tool type.
+    #[serde(rename = "type")]
+    #[serde(default)]
+    pub tool_type: Option<String>,
+    /// Function call delta.
+    #[serde(default)]
+    pub function: Option<FunctionCallDelta>,
+}
+
+/// Incremental function call.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FunctionCallDelta {
+    /// Function name.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Fragment of the JSON-encoded arguments.
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
 /// Model pricing information.
 #[derive(Debug, Clone, Deserialize)]
 pub struct ModelPricing {
@@ -373,6 +1890,54 @@ pub struct ModelPricing {
     pub request: Option<String>,
 }
 
+impl ModelPricing {
+    /// Parsed prompt price per token in USD.
+    ///
+    /// Returns `None` if the price is unparseable or negative (used by
+    /// router models whose price depends on the routed model).
+    pub fn prompt_price(&self) -> Option<f64> {
+        parse_price(&self.prompt)
+    }
+
+    /// Parsed completion price per token in USD.
+    ///
+    /// Returns `None` if the price is unparseable or negative.
+    pub fn completion_price(&self) -> Option<f64> {
+        parse_price(&self.completion)
+    }
+
+    /// Prompt price per million tokens in USD.
+    ///
+    /// Returns `None` for free (`"0"`) or unparseable prices.
+    pub fn prompt_per_million(&self) -> Option<f64> {
+        per_million(self.prompt_price())
+    }
+
+    /// Completion price per million tokens in USD.
+    ///
+    /// Returns `None` for free (`"0"`) or unparseable prices.
+    pub fn completion_per_million(&self) -> Option<f64> {
+        per_million(self.completion_price())
+    }
+
+    /// Combined prompt and completion price per token in USD.
+    pub fn combined_price(&self) -> Option<f64> {
+        Some(self.prompt_price()? + self.completion_price()?)
+    }
+}
+
+fn per_million(price: Option<f64>) -> Option<f64> {
+    price.filter(|p| *p > 0.0).map(|p| p * 1_000_000.0)
+}
+
+fn parse_price(price: &str) -> Option<f64> {
+    price
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|p| p.is_finite() && *p >= 0.0)
+}
+
 /// Model information from OpenRouter.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Model {
@@ -393,6 +1958,284 @@ pub struct Model {
     /// Model architecture.
     #[serde(default)]
     pub architecture: Option<ModelArchitecture>,
+    /// Request parameters honored by this model (e.g., "tools", "response_format").
+    #[serde(default)]
+    pub supported_parameters: Vec<String>,
+    /// Per-request token limits, if the model enforces any.
+    #[serde(default)]
+    pub per_request_limits: Option<serde_json::Value>,
+    /// Whether the model is deprecated.
+    #[serde(default)]
+    pub deprecated: Option<bool>,
+    /// Whether the model is hidden from listings.
+    #[serde(default)]
+    pub hidden: Option<bool>,
+    /// Date after which the model stops being served (e.g., "2025-06-30").
+    #[serde(default)]
+    pub expiration_date: Option<String>,
+}
+
+/// Split a model ID into author and slug on the first `/`.
+///
+/// `"openai/gpt-4o"` becomes `("openai", "gpt-4o")`; further slashes stay in
+/// the slug. Returns `None` if the ID has no author prefix.
+pub fn split_model_id(id: &str) -> Option<(&str, &str)> {
+    id.split_once('/')
+}
+
+/// Suffix of model variants with web search enabled.
+const ONLINE_SUFFIX: &str = ":online";
+
+/// Strip the `:online` suffix from a model ID.
+///
+/// `"openai/gpt-4o:online"` becomes `"openai/gpt-4o"`; other IDs are
+/// returned unchanged. Append `:online` to go the other way.
+pub fn base_model_id(id: &str) -> &str {
+    id.strip_suffix(ONLINE_SUFFIX).unwrap_or(id)
+}
+
+impl Model {
+    /// Model author (e.g., "openai" for "openai/gpt-4o").
+    ///
+    /// Empty if the ID has no author prefix.
+    pub fn author(&self) -> &str {
+        split_model_id(&self.id).map_or("", |(author, _)| author)
+    }
+
+    /// Model slug (e.g., "gpt-4o" for "openai/gpt-4o").
+    ///
+    /// The full ID if it has no author prefix.
+    pub fn slug(&self) -> &str {
+        split_model_id(&self.id).map_or(self.id.as_str(), |(_, slug)| slug)
+    }
+
+    /// Check if the model honors the given request parameter.
+    pub fn supports_parameter(&self, parameter: &str) -> bool {
+        self.supported_parameters.iter().any(|p| p == parameter)
+    }
+
+    /// Check if the model is free to use.
+    ///
+    /// True for `:free` variants and for models whose prompt and completion
+    /// prices are both zero.
+    pub fn is_free(&self) -> bool {
+        self.id.ends_with(":free") || self.pricing.combined_price() == Some(0.0)
+    }
+
+    /// Check if a human-entered name refers to this model (see [`ModelList::resolve`]).
+    pub fn matches(&self, query: &str) -> bool {
+        self.match_rank(query).is_some()
+    }
+
+    /// How closely `query` matches, lower being closer: exact ID, slug with
+    /// the author omitted, ID suffix, then every query word found in the ID
+    /// or display name. Case-insensitive.
+    fn match_rank(&self, query: &str) -> Option<u8> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return None;
+        }
+        let id = self.id.to_lowercase();
+        let name = self.name.to_lowercase();
+
+        if id == query {
+            Some(0)
+        } else if self.slug().eq_ignore_ascii_case(&query) {
+            Some(1)
+        } else if id.ends_with(&query) {
+            Some(2)
+        } else if query
+            .split_whitespace()
+            .all(|word| id.contains(word) || name.contains(word))
+        {
+            Some(3)
+        } else {
+            None
+        }
+    }
+
+    /// Check if this is an `:online` variant with web search enabled.
+    pub fn is_online_variant(&self) -> bool {
+        self.id.ends_with(ONLINE_SUFFIX)
+    }
+
+    /// Check if the model is deprecated or scheduled to stop being served.
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated == Some(true) || self.expiration_date.is_some()
+    }
+
+    /// Check if the model accepts the given input modality (e.g., "image", "audio").
+    ///
+    /// Uses `input_modalities` when present, falling back to the input side of
+    /// the `modality` string (e.g., "text+image->text"). Models without
+    /// architecture information are assumed to accept text only.
+    pub fn supports_input_modality(&self, modality: &str) -> bool {
+        let Some(architecture) = &self.architecture else {
+            return modality == "text";
+        };
+        if !architecture.input_modalities.is_empty() {
+            return architecture.input_modalities.iter().any(|m| m == modality);
+        }
+        architecture
+            .modality
+            .as_deref()
+            .and_then(|m| m.split("->").next())
+            .map_or(modality == "text", |input| {
+                input.split('+').any(|m| m == modality)
+            })
+    }
+
+    /// Check if the model accepts audio input.
+    pub fn supports_audio_input(&self) -> bool {
+        self.supports_input_modality("audio")
+    }
+
+    /// Check if the model supports tool calling.
+    pub fn supports_tools(&self) -> bool {
+        self.supports_parameter("tools")
+    }
+
+    /// Check if the model supports `response_format`.
+    pub fn supports_response_format(&self) -> bool {
+        self.supports_parameter("response_format")
+    }
+
+    /// Check if the model supports JSON schema `response_format` (structured outputs).
+    pub fn supports_structured_outputs(&self) -> bool {
+        self.supports_parameter("structured_outputs")
+    }
+
+    /// Check if the model supports reasoning.
+    pub fn supports_reasoning(&self) -> bool {
+        self.supports_parameter("reasoning")
+    }
+}
+
+/// Criteria for selecting models from the model list.
+#[derive(Debug, Clone, Default)]
+pub struct ModelFilter {
+    /// Request parameters the model must support (see [`Model::supported_parameters`]).
+    pub parameters: Vec<String>,
+    /// Input modality the model must accept (e.g., "image"; see
+    /// [`Model::supports_input_modality`]).
+    pub modality: Option<String>,
+    /// Minimum context length in tokens.
+    pub min_context_length: Option<usize>,
+}
+
+impl ModelFilter {
+    /// Create an empty filter that matches every model.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require support for a request parameter.
+    pub fn with_parameter(mut self, parameter: impl Into<String>) -> Self {
+        self.parameters.push(parameter.into());
+        self
+    }
+
+    /// Require tool calling support.
+    pub fn with_tools(self) -> Self {
+        self.with_parameter("tools")
+    }
+
+    /// Require `response_format` support.
+    pub fn with_response_format(self) -> Self {
+        self.with_parameter("response_format")
+    }
+
+    /// Require the model to accept the given input modality.
+    pub fn with_modality(mut self, modality: impl Into<String>) -> Self {
+        self.modality = Some(modality.into());
+        self
+    }
+
+    /// Require a minimum context length.
+    pub fn with_min_context_length(mut self, tokens: usize) -> Self {
+        self.min_context_length = Some(tokens);
+        self
+    }
+
+    /// Check if a model satisfies every criterion of this filter.
+    pub fn matches(&self, model: &Model) -> bool {
+        if !self.parameters.iter().all(|p| model.supports_parameter(p)) {
+            return false;
+        }
+
+        if let Some(min) = self.min_context_length {
+            if model.context_length < min {
+                return false;
+            }
+        }
+
+        if let Some(modality) = &self.modality {
+            if !model.supports_input_modality(modality) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// A model capability to query for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// Tool calling.
+    Tools,
+    /// Image input.
+    Vision,
+    /// `response_format` support.
+    ResponseFormat,
+    /// Reasoning support.
+    Reasoning,
+    /// Context length of at least the given number of tokens.
+    MinContext(usize),
+}
+
+impl Capability {
+    /// Check if a model has this capability.
+    pub fn is_met_by(self, model: &Model) -> bool {
+        match self {
+            Self::Tools => model.supports_tools(),
+            Self::Vision => model.supports_input_modality("image"),
+            Self::ResponseFormat => model.supports_response_format(),
+            Self::Reasoning => model.supports_reasoning(),
+            Self::MinContext(tokens) => model.context_length >= tokens,
+        }
+    }
+}
+
+/// How to handle content a model cannot accept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContentFallback {
+    /// Reject the request.
+    #[default]
+    Error,
+    /// Replace image parts with a text placeholder.
+    DropImages,
+}
+
+/// Placeholder substituted for images by [`ContentFallback::DropImages`].
+pub const IMAGE_PLACEHOLDER: &str = "[image omitted]";
+
+/// Availability of a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelStatus {
+    /// Model is listed and can be used.
+    Available,
+    /// Model is listed but deprecated; it still works but may disappear.
+    Deprecated,
+    /// Model is not listed.
+    NotFound,
+}
+
+impl ModelStatus {
+    /// Check if the model can be used (including deprecated models).
+    pub fn is_available(self) -> bool {
+        matches!(self, Self::Available | Self::Deprecated)
+    }
 }
 
 /// Top provider details.
@@ -421,6 +2264,12 @@ pub struct ModelArchitecture {
     /// Instruction type.
     #[serde(default)]
     pub instruct_type: Option<String>,
+    /// Accepted input modalities (e.g., "text", "image", "audio").
+    #[serde(default)]
+    pub input_modalities: Vec<String>,
+    /// Produced output modalities.
+    #[serde(default)]
+    pub output_modalities: Vec<String>,
 }
 
 /// List of models.
@@ -430,6 +2279,72 @@ pub struct ModelList {
     pub data: Vec<Model>,
 }
 
+impl ModelList {
+    /// Group models by author, each group sorted by model ID.
+    ///
+    /// Models without an author prefix are grouped under `""`.
+    pub fn group_by_author(&self) -> std::collections::BTreeMap<String, Vec<&Model>> {
+        let mut groups: std::collections::BTreeMap<String, Vec<&Model>> =
+            std::collections::BTreeMap::new();
+        for model in &self.data {
+            groups
+                .entry(model.author().to_string())
+                .or_default()
+                .push(model);
+        }
+        for models in groups.values_mut() {
+            models.sort_by(|a, b| a.id.cmp(&b.id));
+        }
+        groups
+    }
+
+    /// Resolve a human-entered model name such as `"gpt-4o"` or
+    /// `"claude sonnet"` to a single model.
+    ///
+    /// Returns the closest match, or `None` if nothing matches or several
+    /// models match equally well (see [`resolve_all`](Self::resolve_all)).
+    pub fn resolve(&self, query: &str) -> Option<&Model> {
+        let mut ranked = self.ranked_matches(query).into_iter();
+        let (rank, best) = ranked.next()?;
+        match ranked.next() {
+            Some((next, _)) if next == rank => None,
+            _ => Some(best),
+        }
+    }
+
+    /// All models matching a human-entered name, closest first (ties by ID).
+    pub fn resolve_all(&self, query: &str) -> Vec<&Model> {
+        self.ranked_matches(query)
+            .into_iter()
+            .map(|(_, model)| model)
+            .collect()
+    }
+
+    fn ranked_matches(&self, query: &str) -> Vec<(u8, &Model)> {
+        let mut matches: Vec<(u8, &Model)> = self
+            .data
+            .iter()
+            .filter_map(|model| Some((model.match_rank(query)?, model)))
+            .collect();
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.id.cmp(&b.1.id)));
+        matches
+    }
+
+    /// Distinct model authors, sorted.
+    pub fn authors(&self) -> Vec<&str> {
+        let mut authors: Vec<&str> = self.data.iter().map(Model::author).collect();
+        authors.sort_unstable();
+        authors.dedup();
+        authors
+    }
+}
+
+/// Envelope of the /api/v1/models/{author}/{slug} response.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ModelResponse {
+    pub data: Model,
+}
+
 /// Generation statistics (returned by /api/v1/generation).
 #[derive(Debug, Clone, Deserialize)]
 pub struct GenerationStats {
@@ -480,6 +2395,127 @@ pub struct CreditsData {
     pub is_free_tier: Option<bool>,
 }
 
+/// Account-wide credit totals (returned by /api/v1/credits).
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountCredits {
+    /// Total credits purchased in USD.
+    pub total_credits: f64,
+    /// Total credits used in USD.
+    pub total_usage: f64,
+}
+
+impl AccountCredits {
+    /// Credits remaining in USD.
+    pub fn remaining(&self) -> f64 {
+        self.total_credits - self.total_usage
+    }
+}
+
+/// Envelope of the /api/v1/credits response.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct AccountCreditsResponse {
+    pub data: AccountCredits,
+}
+
+/// Rate limit state reported by the `X-RateLimit-*` response headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// Request limit for the current window (`X-RateLimit-Limit`).
+    pub limit: Option<u64>,
+    /// Requests remaining in the current window (`X-RateLimit-Remaining`).
+    pub remaining: Option<u64>,
+    /// Window reset time in Unix epoch milliseconds (`X-RateLimit-Reset`).
+    pub reset_at: Option<u64>,
+}
+
+impl RateLimitInfo {
+    /// Parse rate limit headers, returning `None` if none are present.
+    pub fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let parse = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|s| s.trim().parse::<u64>().ok())
+        };
+
+        let info = Self {
+            limit: parse("x-ratelimit-limit"),
+            remaining: parse("x-ratelimit-remaining"),
+            reset_at: parse("x-ratelimit-reset"),
+        };
+        (info.limit.is_some() || info.remaining.is_some() || info.reset_at.is_some())
+            .then_some(info)
+    }
+
+    /// Requests that can still be made before hitting the limit.
+    ///
+    /// Returns the full limit once the window has reset.
+    pub fn remaining_requests(&self) -> Option<u64> {
+        if self.reset_in().is_zero() && self.reset_at.is_some() {
+            return self.limit.or(self.remaining);
+        }
+        self.remaining
+    }
+
+    /// Time until the rate limit window resets (zero if unknown or already reset).
+    pub fn reset_in(&self) -> std::time::Duration {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        std::time::Duration::from_millis(self.reset_at.unwrap_or(0).saturating_sub(now))
+    }
+}
+
+/// Metadata of a successful HTTP response, useful for debugging and support requests.
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    /// HTTP status code.
+    pub status: u16,
+    /// Request ID (`x-openrouter-request-id`, `x-request-id`, or `request-id` header).
+    pub request_id: Option<String>,
+    /// Generation ID (the response body `id`), usable with
+    /// [`Client::get_generation`](crate::Client::get_generation).
+    ///
+    /// May differ from `request_id`; both help correlate activity logs.
+    pub generation_id: Option<String>,
+    /// All response headers.
+    pub headers: reqwest::header::HeaderMap,
+}
+
+impl ResponseMeta {
+    /// Capture metadata from a response.
+    pub(crate) fn from_response(response: &reqwest::Response) -> Self {
+        let headers = response.headers().clone();
+        let request_id = ["x-openrouter-request-id", "x-request-id", "request-id"]
+            .iter()
+            .find_map(|name| headers.get(*name))
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        Self {
+            status: response.status().as_u16(),
+            request_id,
+            generation_id: None,
+            headers,
+        }
+    }
+
+    /// OpenRouter diagnostic headers (`x-or-*` and `x-openrouter-*`).
+    pub fn openrouter_headers(&self) -> std::collections::BTreeMap<String, String> {
+        self.headers
+            .iter()
+            .filter(|(name, _)| {
+                let name = name.as_str();
+                name.starts_with("x-or-") || name.starts_with("x-openrouter-")
+            })
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect()
+    }
+}
+
 /// Error response from the API.
 #[derive(Debug, Clone, Deserialize)]
 pub struct ErrorResponse {
@@ -499,4 +2535,7 @@ pub struct ErrorDetail {
     /// Error code.
     #[serde(default)]
     pub code: Option<i32>,
+    /// Provider-specific details (raw provider error, flagged content, etc.).
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
 }